@@ -1,242 +1,1355 @@
-use std::collections::HashMap;
-use std::env::{consts, current_dir};
-use std::fs::create_dir_all;
+use std::collections::{BTreeMap, HashSet};
+use std::env::consts;
+use std::error::Error as StdError;
+use std::fs::{create_dir_all, metadata, read_to_string, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::sync::mpsc::channel;
-
-use chrono::offset::Local;
-use lazy_static::lazy_static;
-use notify::{op, raw_watcher, RawEvent, RecursiveMode, Watcher};
-
-use crate::log_utils::{log_error_to_console, log_info_to_console, log_success_to_console};
-
-lazy_static! {
-    pub static ref PLATFORM_TOOLCHAINS: HashMap<&'static str, &'static str> = {
-        let mut toolchains = HashMap::new();
-        toolchains.insert("android.arm", "aarch64-linux-android");
-        toolchains.insert("android", "x86_64-linux-android");
-        toolchains.insert("windows", "x86_64-pc-windows-gnu");
-        toolchains.insert("linux", "x86_64-unknown-linux-gnu");
-        toolchains.insert("macos", "x86_64-apple-darwin");
-        toolchains
+use std::process::{Child, ChildStdout, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use std::{io, thread};
+
+use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
+
+use crate::android_utils::add_android_ndk_env;
+use crate::cargo_config::find_target_linker;
+use crate::config_utils::PlatformBackend;
+use crate::emscripten_utils::{add_emscripten_args, find_emcc};
+use crate::error::{Context, Error, Result};
+use crate::gdnlib::Gdnlib;
+use crate::install_utils::{
+    install_dynamic_library, install_versioned_dynamic_library,
+    remove_old_versioned_dynamic_libraries,
+};
+use crate::log_utils::{
+    get_verbosity, log_command_to_console, log_error_to_console, log_info_to_console,
+    log_success_to_console, log_table_to_console, log_warning_to_console, report, Event, Verbosity,
+};
+use crate::macho_utils::create_universal_binary;
+use crate::path_utils::get_godot_res_path;
+use crate::platforms::{get_platform, get_platform_names, BuildBackend, Platform};
+use crate::process_utils::{kill_process_groups_on_interrupt, ProcessGroups};
+
+/// The settings used to build the library and install its dynamic library in
+/// the Godot project, which are the same for every platform.
+#[derive(Debug, Clone, Copy)]
+pub struct BuildSettings<'a> {
+    /// The path to the library's directory.
+    pub library_dir: &'a Path,
+    /// The name of the Rust library.
+    pub rust_library_name: &'a str,
+    /// The absolute path to the Godot project.
+    pub godot_project_absolute_path: &'a str,
+    /// The cargo profile to build with, like `dev`, `release`, or a custom
+    /// profile defined in the library's Cargo.toml.
+    pub profile: &'a str,
+    /// The extra arguments to pass to `cargo build` or `cross build`.
+    pub cargo_args: &'a [String],
+    /// Indicates whether the Godot project is a plugin or not.
+    pub is_plugin: bool,
+    /// Indicates whether the dynamic library should be copied to a new
+    /// versioned file for every build so that Godot can reload it safely.
+    pub is_versioned: bool,
+    /// The backends chosen for platforms in the configuration instead of
+    /// their default ones.
+    pub platform_backends: &'a BTreeMap<String, PlatformBackend>,
+    /// The process groups to spawn the builds in, if the builds can be
+    /// cancelled.
+    pub process_groups: Option<&'a ProcessGroups>,
+}
+
+/// Builds the library for the specified platform with the platform's
+/// [`BuildBackend`], which is chosen by [`Platform::get_build_backend`].
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `platform` - The platform to target the build for.
+pub fn build_for_platform(settings: &BuildSettings, platform: &str) -> Result<()> {
+    log_info_to_console(&format!("Building library for {}", platform));
+    report(Event::StepStarted {
+        step: "build",
+        platform: Some(platform),
+    });
+
+    let mut build = spawn_build(settings, platform, None)?;
+    let status = build.child.wait();
+
+    finish_build(build, status, settings, platform).map(|_| ())
+}
+
+/// The outcome of building the library for one platform out of several.
+struct PlatformBuild {
+    /// The platform that was built.
+    platform: String,
+    /// How long the build took, including copying the dynamic library.
+    duration: Duration,
+    /// The size of the dynamic library that was copied to the Godot
+    /// project, if the build succeeded.
+    artifact_size: Option<u64>,
+    /// The result of the build.
+    result: Result<()>,
+}
+
+/// Builds the library for the native platform and other platforms at the
+/// same time, with up to `jobs` builds running at once. The output of each
+/// build is written to a log file under `target/godot-rust-cli/` instead of
+/// the console and a table summarizing the builds is logged once they're all
+/// done. Every build runs even if some fail and the error of the first one
+/// that failed is returned.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `native_platform` - The user's native platform.
+/// `other_platforms` - The other platforms to build for.
+/// `jobs` - The number of builds that can run at once.
+pub fn build_for_platforms(
+    settings: &BuildSettings,
+    native_platform: &str,
+    other_platforms: &[String],
+    jobs: usize,
+) -> Result<()> {
+    let logs_dir = create_build_logs_dir(settings)?;
+
+    // Only the native platform's dynamic library is versioned.
+    let other_settings = BuildSettings {
+        is_versioned: false,
+        ..*settings
     };
+    let builds: Vec<(&BuildSettings, &str)> = std::iter::once((settings, native_platform))
+        .chain(
+            other_platforms
+                .iter()
+                .map(|platform| (&other_settings, platform.as_str())),
+        )
+        .collect();
+    let platform_builds = run_logged_builds(&builds, jobs, &logs_dir);
+
+    log_build_summary(settings, &logs_dir, platform_builds.iter());
+
+    match platform_builds
+        .into_iter()
+        .find_map(|platform_build| platform_build.result.err())
+    {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
 }
 
-/// Builds the library for the specified platform. If the platform is not the
-/// user's native platform, then the `cross` command will be used.
+/// Creates the directory that the build logs are written to and returns its
+/// path. The logs are put in the target directory so that they're ignored
+/// along with the rest of the build output.
 ///
 /// # Arguments
 ///
-/// `rust_library_name`             - The name of the Rust library.
-/// `godot_project_absolute_path`   - The absolute path to the Godot project.
-/// `platform`                      - The platform to target the build for.
-/// `is_release`                    - Indicates whether the build is a release build or not. This is passed in by the user as an argument to the `build` command.
-/// `is_plugin`                     - Indicates whether the Godot project is a plugin or not.
-pub fn build_for_platform(
-    rust_library_name: &str,
-    godot_project_absolute_path: &str,
-    platform: &str,
-    is_release: bool,
-    is_plugin: bool,
-) {
+/// `settings` - The settings to build the library with.
+fn create_build_logs_dir(settings: &BuildSettings) -> Result<PathBuf> {
+    let logs_dir = get_target_dir(settings.library_dir)
+        .unwrap_or_else(|_| settings.library_dir.join("target"))
+        .join("godot-rust-cli");
+    create_dir_all(&logs_dir).context("Unable to create the directory for the build logs")?;
+
+    Ok(logs_dir)
+}
+
+/// Runs builds with their output written to log files, with up to `jobs` of
+/// them running at the same time. Returns the builds in the order that they
+/// were passed in.
+///
+/// # Arguments
+///
+/// `builds` - The settings and platform of each build.
+/// `jobs` - The number of builds that can run at once.
+/// `logs_dir` - The directory to write the log files to.
+fn run_logged_builds(
+    builds: &[(&BuildSettings, &str)],
+    jobs: usize,
+    logs_dir: &Path,
+) -> Vec<PlatformBuild> {
+    // Each worker takes the next build that hasn't been started until there
+    // are none left.
+    let next_build = Mutex::new(builds.iter().enumerate());
+    let mut platform_builds: Vec<(usize, PlatformBuild)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, builds.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut platform_builds = vec![];
+                    loop {
+                        let next = next_build.lock().ok().and_then(|mut builds| builds.next());
+                        match next {
+                            Some((index, (settings, platform))) => platform_builds
+                                .push((index, run_logged_build(settings, platform, logs_dir))),
+                            None => return platform_builds,
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+    platform_builds.sort_by_key(|(index, _)| *index);
+
+    platform_builds
+        .into_iter()
+        .map(|(_, platform_build)| platform_build)
+        .collect()
+}
+
+/// Builds the library for a platform with the output of the build written to
+/// a log file named after the platform.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `platform` - The platform to target the build for.
+/// `logs_dir` - The directory to write the log file to.
+fn run_logged_build(settings: &BuildSettings, platform: &str, logs_dir: &Path) -> PlatformBuild {
     log_info_to_console(&format!("Building library for {}", platform));
+    report(Event::StepStarted {
+        step: "build",
+        platform: Some(platform),
+    });
+
+    let started_at = Instant::now();
+    let log_path = get_build_log_path(logs_dir, platform);
+    let result = File::create(&log_path)
+        .context(format!("Unable to create the build log for {}", platform))
+        .and_then(|log_file| spawn_build(settings, platform, Some(log_file)))
+        .and_then(|mut build| {
+            let status = build.child.wait();
+            finish_build(build, status, settings, platform)
+        });
+
+    // Builds that couldn't be run don't have any output so the error is
+    // added to the log to explain why the build failed.
+    if let Err(e) = &result {
+        if let Ok(mut log_file) = OpenOptions::new().append(true).create(true).open(&log_path) {
+            let _ = writeln!(log_file, "Error: {}", e);
+            let mut source = e.source();
+            while let Some(cause) = source {
+                let _ = writeln!(log_file, "Caused by: {}", cause);
+                source = cause.source();
+            }
+        }
+    }
+
+    PlatformBuild {
+        platform: platform.to_string(),
+        duration: started_at.elapsed(),
+        artifact_size: result
+            .as_ref()
+            .ok()
+            .and_then(|installed_file_path| metadata(installed_file_path).ok())
+            .map(|installed_file| installed_file.len()),
+        result: result.map(|_| ()),
+    }
+}
+
+/// Returns the path to the log file of the build for a platform.
+///
+/// # Arguments
+///
+/// `logs_dir` - The directory that the log files are in.
+/// `platform` - The platform that the build targeted.
+fn get_build_log_path(logs_dir: &Path, platform: &str) -> PathBuf {
+    logs_dir.join(format!("{}.log", platform))
+}
+
+/// Logs a table with the platform, profile, duration, dynamic library size,
+/// and result of each build.
+///
+/// # Arguments
+///
+/// `settings` - The settings that the library was built with.
+/// `logs_dir` - The directory that the log files are in.
+/// `platform_builds` - The builds to log.
+fn log_build_summary<'a>(
+    settings: &BuildSettings,
+    logs_dir: &Path,
+    platform_builds: impl Iterator<Item = &'a PlatformBuild>,
+) {
+    let mut rows: Vec<Vec<String>> = vec![vec![
+        "Platform".to_string(),
+        "Profile".to_string(),
+        "Duration".to_string(),
+        "Size".to_string(),
+        "Result".to_string(),
+    ]];
+    for platform_build in platform_builds {
+        report(Event::BuildFinished {
+            platform: &platform_build.platform,
+            profile: settings.profile,
+            duration_secs: platform_build.duration.as_secs_f64(),
+            artifact_size: platform_build.artifact_size,
+            succeeded: platform_build.result.is_ok(),
+        });
+        rows.push(vec![
+            platform_build.platform.clone(),
+            settings.profile.to_string(),
+            format!("{:.2}s", platform_build.duration.as_secs_f32()),
+            platform_build
+                .artifact_size
+                .map(format_size)
+                .unwrap_or_else(|| "-".to_string()),
+            match &platform_build.result {
+                Ok(()) => "succeeded".to_string(),
+                Err(_) => format!(
+                    "failed, see {}",
+                    get_build_log_path(logs_dir, &platform_build.platform).display()
+                ),
+            },
+        ]);
+    }
+
+    log_table_to_console(&rows);
+}
+
+/// Returns a number of bytes as a human readable size.
+///
+/// # Arguments
+///
+/// `bytes` - The number of bytes.
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
 
+/// A build that's running along with the thread that reads the dynamic
+/// libraries that it reports building.
+struct RunningBuild {
+    /// The `cargo build` or `cross build` process.
+    child: Child,
+    /// The thread reading the dynamic libraries from the build's output.
+    dynamic_libraries: JoinHandle<Vec<DynamicLibraryArtifact>>,
+    /// Indicates whether the build is a `cross` build or not.
+    is_cross: bool,
+}
+
+/// A dynamic library that cargo reported building.
+struct DynamicLibraryArtifact {
+    /// The name of the crate's library target.
+    name: String,
+    /// The paths to the files that were built for the library.
+    filenames: Vec<PathBuf>,
+}
+
+/// Starts building the library for a platform.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `platform` - The platform to target the build for.
+/// `log_file` - The file to write the output of the build to instead of the console, if any.
+fn spawn_build(
+    settings: &BuildSettings,
+    platform: &str,
+    log_file: Option<File>,
+) -> Result<RunningBuild> {
+    let crate_type = get_platform_to_build(platform)?.crate_type;
+    let (mut build_command, is_cross) = get_build_command(settings, platform)?;
+    if let Some(log_file) = log_file {
+        build_command.stderr(log_file);
+    }
+    log_command_to_console(&build_command);
+    let mut child = match settings.process_groups {
+        Some(process_groups) => process_groups.spawn(&mut build_command),
+        None => build_command.spawn(),
+    }
+    .map_err(|e| get_build_error(platform, is_cross, None, Some(e)))?;
+
+    // The messages have to be read while the build runs or it would block
+    // once the pipe is full.
+    let stdout = child.stdout.take();
+    let dynamic_libraries = thread::spawn(move || {
+        stdout
+            .map(|stdout| read_dynamic_library_artifacts(stdout, crate_type))
+            .unwrap_or_default()
+    });
+
+    Ok(RunningBuild {
+        child,
+        dynamic_libraries,
+        is_cross,
+    })
+}
+
+/// Checks that a build that exited succeeded and then copies the dynamic
+/// library that it reported building over to the Godot project. Returns the
+/// path to the dynamic library in the Godot project.
+///
+/// # Arguments
+///
+/// `build` - The build that exited.
+/// `status` - The result of waiting for the build to exit.
+/// `settings` - The settings that the library was built with.
+/// `platform` - The platform that the build targeted.
+fn finish_build(
+    build: RunningBuild,
+    status: io::Result<ExitStatus>,
+    settings: &BuildSettings,
+    platform: &str,
+) -> Result<PathBuf> {
+    let dynamic_libraries = build.dynamic_libraries.join().unwrap_or_default();
+    if let Some(process_groups) = settings.process_groups {
+        process_groups.release(&build.child);
+    }
+
+    // Make sure that the build succeeded.
+    match status {
+        Ok(status) if status.success() => (),
+        Ok(status) => {
+            return Err(get_build_error(
+                platform,
+                build.is_cross,
+                Some(status),
+                None,
+            ))
+        }
+        Err(e) => return Err(get_build_error(platform, build.is_cross, None, Some(e))),
+    }
+
+    let dynamic_library_file_path =
+        find_dynamic_library(&dynamic_libraries, settings, platform, build.is_cross)?;
+
+    install_build(settings, platform, &dynamic_library_file_path)
+}
+
+/// Reads the `compiler-artifact` messages that cargo writes when it's run
+/// with `--message-format=json` and returns the libraries of a crate type,
+/// which is `cdylib` for every platform other than ios, that it reports
+/// building. Lines that aren't messages are ignored.
+///
+/// # Arguments
+///
+/// `stdout` - The output of the build.
+/// `crate_type` - The crate type of the libraries to return.
+fn read_dynamic_library_artifacts(
+    stdout: ChildStdout,
+    crate_type: &str,
+) -> Vec<DynamicLibraryArtifact> {
+    BufReader::new(stdout)
+        .lines()
+        .map_while(|line| line.ok())
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter(|message| {
+            message["target"]["crate_types"]
+                .as_array()
+                .is_some_and(|crate_types| {
+                    crate_types
+                        .iter()
+                        .any(|artifact_crate_type| artifact_crate_type == crate_type)
+                })
+        })
+        .map(|message| DynamicLibraryArtifact {
+            name: message["target"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            filenames: message["filenames"]
+                .as_array()
+                .map(|filenames| {
+                    filenames
+                        .iter()
+                        .filter_map(|filename| filename.as_str())
+                        .map(PathBuf::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Returns the path to the dynamic library for the platform out of the ones
+/// that the build reported. This is wherever cargo put it, which depends on
+/// `CARGO_TARGET_DIR`, the `build.target-dir` config, and whether the library
+/// is in a workspace. For platforms with more than one target it's the
+/// universal binary that the dynamic libraries for each target are merged
+/// into.
+///
+/// # Arguments
+///
+/// `dynamic_libraries` - The dynamic libraries that the build reported.
+/// `settings` - The settings that the library was built with.
+/// `platform` - The platform that the build targeted.
+/// `is_cross` - Indicates whether the build was a `cross` build or not.
+fn find_dynamic_library(
+    dynamic_libraries: &[DynamicLibraryArtifact],
+    settings: &BuildSettings,
+    platform: &str,
+    is_cross: bool,
+) -> Result<PathBuf> {
+    let platform_to_build = get_platform_to_build(platform)?;
+    let not_reported = || Error::ArtifactNotReported {
+        name: settings.rust_library_name.to_string(),
+        crate_type: platform_to_build.crate_type,
+    };
+
+    // Path dependencies can be dynamic libraries too so the library is found
+    // by its name unless it's the only one. The library is reported once for
+    // every target that it was built for.
+    let is_only_library = dynamic_libraries
+        .windows(2)
+        .all(|pair| pair[0].name == pair[1].name);
+    let library_artifacts: Vec<&DynamicLibraryArtifact> = dynamic_libraries
+        .iter()
+        .filter(|dynamic_library| {
+            is_only_library || dynamic_library.name == settings.rust_library_name
+        })
+        .collect();
+
+    // Windows builds also report import libraries and debug info next to
+    // the dll.
+    let dynamic_library_ext = platform_to_build.library_ext;
+    let get_dynamic_library_file_path = |dynamic_library: &&DynamicLibraryArtifact| {
+        dynamic_library
+            .filenames
+            .iter()
+            .find(|filename| {
+                filename
+                    .extension()
+                    .is_some_and(|ext| ext == dynamic_library_ext)
+            })
+            .cloned()
+    };
+
+    if platform_to_build.rust_triples.len() == 1 {
+        let dynamic_library_file_path = library_artifacts
+            .last()
+            .and_then(get_dynamic_library_file_path)
+            .ok_or_else(not_reported)?;
+
+        return get_host_path(settings, dynamic_library_file_path, is_cross);
+    }
+
+    // Each target's dynamic library is under a directory named after the
+    // target.
+    let mut dynamic_library_file_paths: Vec<PathBuf> = vec![];
+    for rust_triple in platform_to_build.rust_triples {
+        let dynamic_library_file_path = library_artifacts
+            .iter()
+            .rev()
+            .filter_map(get_dynamic_library_file_path)
+            .find(|file_path| {
+                file_path
+                    .components()
+                    .any(|component| component.as_os_str() == *rust_triple)
+            })
+            .ok_or_else(not_reported)?;
+
+        dynamic_library_file_paths.push(get_host_path(
+            settings,
+            dynamic_library_file_path,
+            is_cross,
+        )?);
+    }
+
+    create_platform_universal_binary(platform_to_build, &dynamic_library_file_paths)
+}
+
+/// Returns the path on the host of a file that a build reported. Cross runs
+/// cargo in a container that has the target directory mounted at `/target`
+/// so the paths that it reports have to be mapped back to the target
+/// directory on the host.
+///
+/// # Arguments
+///
+/// `settings` - The settings that the library was built with.
+/// `file_path` - The path to the file that the build reported.
+/// `is_cross` - Indicates whether the build was a `cross` build or not.
+fn get_host_path(settings: &BuildSettings, file_path: PathBuf, is_cross: bool) -> Result<PathBuf> {
+    if is_cross && !file_path.exists() {
+        if let Ok(path_in_target_dir) = file_path.strip_prefix("/target") {
+            return Ok(get_target_dir(settings.library_dir)?.join(path_in_target_dir));
+        }
+    }
+
+    Ok(file_path)
+}
+
+/// Merges the dynamic libraries that were built for each of a platform's
+/// targets into a universal binary and returns the path to it. The dynamic
+/// libraries are at `<target dir>/<target>/<profile>/` so the universal
+/// binary is put at `<target dir>/<platform>/<profile>/`.
+///
+/// # Arguments
+///
+/// `platform` - The platform that the dynamic libraries were built for.
+/// `dynamic_library_file_paths` - The paths to the dynamic library built for each target.
+fn create_platform_universal_binary(
+    platform: &Platform,
+    dynamic_library_file_paths: &[PathBuf],
+) -> Result<PathBuf> {
+    let first_file_path = &dynamic_library_file_paths[0];
+    let profile_dir = first_file_path.parent();
+    let target_dir = profile_dir.and_then(Path::parent).and_then(Path::parent);
+    let (profile_dir_name, target_dir, file_name) = match (
+        profile_dir.and_then(Path::file_name),
+        target_dir,
+        first_file_path.file_name(),
+    ) {
+        (Some(profile_dir_name), Some(target_dir), Some(file_name)) => {
+            (profile_dir_name, target_dir, file_name)
+        }
+        _ => return Err(Error::ArtifactMissing(first_file_path.clone())),
+    };
+
+    let universal_binary_dir = target_dir.join(platform.name).join(profile_dir_name);
+    create_dir_all(&universal_binary_dir).context(format!(
+        "Unable to create the directory for the {} universal binary",
+        platform.name
+    ))?;
+
+    let universal_binary_file_path = universal_binary_dir.join(file_name);
+    create_universal_binary(dynamic_library_file_paths, &universal_binary_file_path)?;
+
+    Ok(universal_binary_file_path)
+}
+
+/// Returns the target directory that cargo builds the library in.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+fn get_target_dir(library_dir: &Path) -> Result<PathBuf> {
+    let mut metadata_command = Command::new("cargo");
+    metadata_command
+        .arg("metadata")
+        .arg("--format-version")
+        .arg("1")
+        .arg("--no-deps")
+        .current_dir(library_dir)
+        .stderr(Stdio::inherit());
+    log_command_to_console(&metadata_command);
+    let output = metadata_command
+        .output()
+        .context("Unable to run cargo metadata to find the target directory")?;
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .context("Unable to parse the output of cargo metadata")?;
+
+    metadata["target_directory"]
+        .as_str()
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            Error::Invalid("cargo metadata didn't report a target directory".to_string())
+        })
+}
+
+/// Returns the command that builds the library for a platform and whether
+/// it's a `cross` command or not. The command reports what it built as json
+/// messages on its stdout while errors are still rendered for the user.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `platform` - The platform to target the build for.
+fn get_build_command(settings: &BuildSettings, platform: &str) -> Result<(Command, bool)> {
     // Get the user's native platform to determine whether we need to use the
-    // `cross` command or not.
+    // platform's build backend or not.
     let native_platform = consts::OS.to_lowercase();
 
-    // We need the path to the dynamic library that is built so that we can
-    // copy it over to the Godot project. We do this in several steps.
-    // First, we get the current directory of the Rust library.
-    let current_dir = current_dir().expect("Unable to run build, please try again");
-
-    // Next, we have to determine whether the dynamic library is going to be
-    // output to the `debug` or `release` directory, which we can know by
-    // using the `is_release` argument that was passed to this function.
-    let debug_or_release = if is_release { "release" } else { "debug" };
-
-    // Get the toolchain that we expect the dynamic library to be under for
-    // the platform that we are targeting.
-    let toolchain = PLATFORM_TOOLCHAINS
-        .get(platform)
-        .expect("Unable to run build, please try again");
-
-    // The dynamic library will have a prefix of "lib" for the Linux
-    // platform and "lib" for anything else.
-    let dynamic_library_prefix = if platform == "windows" { "" } else { "lib" };
-
-    // The dynamic library will have an extension of "dll" for windows,
-    // "dylib" for macOS, and "so" for everything else.
-    let dynamic_library_ext = if platform == "windows" {
-        "dll"
-    } else if platform == "macos" {
-        "dylib"
+    let platform_to_build = get_platform_to_build(platform)?;
+
+    // The native platform is always built with cargo for the target that the
+    // cli was built for, other platforms are built with their build backend
+    // for their target.
+    // Platforms with more than one target have all of them built by the
+    // same command.
+    let native_targets: &[&str] = &[env!("TARGET")];
+    let build_backend = if platform == native_platform {
+        None
     } else {
-        "so"
+        Some(platform_to_build.get_build_backend(settings.platform_backends))
+    };
+    let emcc_path = match build_backend {
+        Some(BuildBackend::Emscripten) => find_emcc(),
+        _ => None,
+    };
+    let (program, targets, is_cross) = match build_backend {
+        None => ("cargo", native_targets, false),
+        Some(BuildBackend::Cargo)
+        | Some(BuildBackend::AndroidNdk { .. })
+        | Some(BuildBackend::Zigbuild)
+        | Some(BuildBackend::LocalLinker) => ("cargo", platform_to_build.rust_triples, false),
+        Some(BuildBackend::Cross) => ("cross", platform_to_build.rust_triples, true),
+        // Without the Emscripten SDK on the host the library is built in the
+        // platform's docker image instead.
+        Some(BuildBackend::Emscripten) if emcc_path.is_some() => {
+            ("cargo", platform_to_build.rust_triples, false)
+        }
+        Some(BuildBackend::Emscripten) => ("cross", platform_to_build.rust_triples, true),
     };
 
-    // Combine the prefix, the name of the library, and the extension to get
-    // the expected file name of the dynamic library generated.
-    let dynamic_library_filename = format!(
-        "{}{}.{}",
-        &dynamic_library_prefix, &rust_library_name, &dynamic_library_ext
-    );
+    // Build the command to run with the targets, the profile, and any extra
+    // arguments for cargo. Emscripten side modules rebuild the standard
+    // library, which can only be done on nightly. zigbuild is a cargo
+    // subcommand that takes the same arguments as `cargo build`.
+    let mut build_command = Command::new(program);
+    match build_backend {
+        Some(BuildBackend::Emscripten) => {
+            build_command.arg("+nightly").arg("build");
+        }
+        Some(BuildBackend::Zigbuild) => {
+            build_command.arg("zigbuild");
+        }
+        _ => {
+            build_command.arg("build");
+        }
+    }
 
-    // Finally we can combine everything from above to get the path to the
-    // dynamic library for this build.
-    let dynamic_library_file_path = Path::new(&current_dir)
-        .join("target")
-        .join(toolchain)
-        .join(debug_or_release)
-        .join(&dynamic_library_filename);
+    match build_backend {
+        // Android platforms are linked with the NDK's toolchain on the host.
+        Some(BuildBackend::AndroidNdk { clang_target }) => {
+            add_android_ndk_env(&mut build_command, platform_to_build, clang_target)?;
+        }
+        Some(BuildBackend::Emscripten) => {
+            add_emscripten_args(&mut build_command, platform_to_build, emcc_path.as_ref());
+        }
+        // Without a linker cargo would fall back to the host's linker, which
+        // can't link for another platform.
+        Some(BuildBackend::LocalLinker) => {
+            for rust_triple in platform_to_build.rust_triples {
+                if find_target_linker(settings.library_dir, rust_triple).is_none() {
+                    return Err(Error::Invalid(format!(
+                        "{} is built with the local-linker backend but no linker is configured for {}, set `linker` under `[target.{}]` in .cargo/config.toml",
+                        platform_to_build.name, rust_triple, rust_triple
+                    )));
+                }
+            }
+        }
+        _ => (),
+    }
 
-    if platform == native_platform {
-        // If the platform to build for is the user's native platform then we
-        // can use the cargo build command.
-        let native_target: &str = env!("TARGET");
-
-        // Build the command to run with the native target and whether or not
-        // it's a release build.
-        let mut cargo_build_command = Command::new("cargo");
-        cargo_build_command
-            .arg("build")
-            .arg("--target")
-            .arg(&native_target);
-        if is_release {
-            cargo_build_command.arg("--release");
-        }
-
-        // Run the command and output the status.
-        cargo_build_command
-            .status()
-            .expect("Unable to run the build, please try again");
+    for target in targets {
+        build_command.arg("--target").arg(target);
+    }
+    build_command
+        .arg("--message-format=json-render-diagnostics")
+        .current_dir(settings.library_dir)
+        .stdout(Stdio::piped());
+    add_profile_and_cargo_args(&mut build_command, settings);
+
+    Ok((build_command, is_cross))
+}
+
+/// Adds the arguments for the profile and the extra arguments to a `cargo
+/// build` or `cross build` command. The `dev` and `release` profiles are
+/// passed as `--release` or nothing so that older versions of cargo can
+/// still build them. Quiet and very verbose logging are passed on to cargo as
+/// `--quiet` and `--verbose`.
+///
+/// # Arguments
+///
+/// `build_command` - The command to add the arguments to.
+/// `settings` - The settings to build the library with.
+fn add_profile_and_cargo_args(build_command: &mut Command, settings: &BuildSettings) {
+    match get_verbosity() {
+        Verbosity::Quiet => {
+            build_command.arg("--quiet");
+        }
+        Verbosity::VeryVerbose => {
+            build_command.arg("--verbose");
+        }
+        _ => (),
+    }
+
+    match settings.profile {
+        "dev" => (),
+        "release" => {
+            build_command.arg("--release");
+        }
+        profile => {
+            build_command.arg("--profile").arg(profile);
+        }
+    }
+    build_command.args(settings.cargo_args);
+}
+
+/// Returns the error for a build that couldn't be run or that failed.
+///
+/// # Arguments
+///
+/// `platform` - The platform that the build targeted.
+/// `is_cross` - Indicates whether the build was run with `cross` or not.
+/// `status` - The status that the build exited with, if it could be run.
+/// `source` - The error from trying to run the build, if it couldn't be run.
+fn get_build_error(
+    platform: &str,
+    is_cross: bool,
+    status: Option<ExitStatus>,
+    source: Option<io::Error>,
+) -> Error {
+    let message = format!("Unable to build the library for {}", platform);
+    if is_cross {
+        Error::CrossFailed {
+            message,
+            status,
+            source,
+        }
     } else {
-        // Otherwise we have to use the `cross` command to build the library
-        // for another platform.
-        // Just like with the cargo version we build the command with the
-        // non-native target this time and whether or not it's a release build.
-        let mut cross_build_command = Command::new("cross");
-        cross_build_command
-            .arg("build")
-            .arg("--target")
-            .arg(&toolchain);
-        if is_release {
-            cross_build_command.arg("--release");
-        }
-
-        // Run the command and output the status.
-        cross_build_command
-            .status()
-            .expect("Unable to run the build please try again");
+        Error::CargoFailed {
+            message,
+            status,
+            source,
+        }
     }
+}
+
+/// Returns the platform to build for from the registry of platforms.
+///
+/// # Arguments
+///
+/// `platform` - The name of the platform.
+fn get_platform_to_build(platform: &str) -> Result<&'static Platform> {
+    get_platform(platform).ok_or_else(|| {
+        Error::Invalid(format!(
+            "{} isn't a platform that can be built for, expected one of {}",
+            platform,
+            get_platform_names()
+        ))
+    })
+}
+
+/// Copies the dynamic library that was built for a platform over to the
+/// Godot project and returns the path to the copy.
+///
+/// # Arguments
+///
+/// `settings` - The settings that the library was built with.
+/// `platform` - The platform that the build targeted.
+/// `dynamic_library_file_path` - The path to the dynamic library that was built.
+fn install_build(
+    settings: &BuildSettings,
+    platform: &str,
+    dynamic_library_file_path: &Path,
+) -> Result<PathBuf> {
+    let native_platform = consts::OS.to_lowercase();
+    let platform_to_build = get_platform_to_build(platform)?;
+
+    // Combine the platform's prefix, the name of the library, and the
+    // platform's extension to get the file name that the Godot project
+    // expects the dynamic library to have.
+    let dynamic_library_filename =
+        platform_to_build.get_library_filename(settings.rust_library_name);
+
     // Next, we build the path to where we should copy the dynmaic library
     // over using the godot project path and whether it is a plugin or not.
-    let godot_project_bin_path = if is_plugin {
-        PathBuf::from(godot_project_absolute_path)
+    let godot_project_bin_path = if settings.is_plugin {
+        PathBuf::from(settings.godot_project_absolute_path)
             .join("addons")
-            .join(rust_library_name)
+            .join(settings.rust_library_name)
             .join("gdnative")
             .join("bin")
-            .join(platform)
+            .join(platform_to_build.bin_dir)
     } else {
-        PathBuf::from(godot_project_absolute_path)
+        PathBuf::from(settings.godot_project_absolute_path)
             .join("gdnative")
             .join("bin")
-            .join(platform)
+            .join(platform_to_build.bin_dir)
     };
 
-    // Make sure that the directory to the path we created above exists so
-    // that we can copy the dynamic library over to it.
-    create_dir_all(&godot_project_bin_path).expect("Unable to run the build, please try again");
+    // Finally we can install the dynamic library in the Godot project, which
+    // creates the directory we created the path to above if needed. Versioned
+    // builds get a new file every time so that Godot never has the dynamic
+    // library it loaded overwritten.
+    let godot_project_bin_file_path = godot_project_bin_path.join(&dynamic_library_filename);
+    let installed_file_path = if settings.is_versioned {
+        install_versioned_dynamic_library(dynamic_library_file_path, &godot_project_bin_file_path)?
+    } else {
+        if !install_dynamic_library(dynamic_library_file_path, &godot_project_bin_file_path)? {
+            log_info_to_console(&format!(
+                "The dynamic library for {} is unchanged, skipping the copy",
+                &platform
+            ));
+        }
+        godot_project_bin_file_path.clone()
+    };
 
-    // Finally we can copy the dynamic library over to the Godot project.
-    println!(
-        "{:?} {:?}",
-        dynamic_library_file_path, godot_project_bin_path
-    );
-    let mut copy_dynamic_library_command = Command::new("cp");
-    copy_dynamic_library_command
-        .arg(dynamic_library_file_path)
-        .arg(godot_project_bin_path);
-    copy_dynamic_library_command
-        .output()
-        .expect("Unable to copy file");
+    // Only the native platform's dynamic library is ever versioned so it's
+    // the only entry in the gdnlib file that needs to follow it. This also
+    // points it back to the unversioned dynamic library after a versioned
+    // build.
+    if platform == native_platform {
+        // Godot can still load an unversioned build through the entry that
+        // it already has so a gdnlib file that can't be updated only fails
+        // versioned builds.
+        if let Err(e) = use_dynamic_library_in_gdnlib(
+            settings.godot_project_absolute_path,
+            &godot_project_bin_path,
+            settings.rust_library_name,
+            platform,
+            &installed_file_path,
+        ) {
+            if settings.is_versioned {
+                return Err(e);
+            }
+            log_warning_to_console(&format!(
+                "Unable to point the gdnlib file to the dynamic library: {}",
+                e
+            ));
+        }
+
+        let removed_copies = remove_old_versioned_dynamic_libraries(
+            &godot_project_bin_file_path,
+            &installed_file_path,
+        );
+        if removed_copies > 0 {
+            log_info_to_console(&format!(
+                "Removed {} old versioned copies of the dynamic library",
+                removed_copies
+            ));
+        }
+    }
 
     log_success_to_console(&format!("Build complete for platform {}", &platform));
+    report(Event::ArtifactInstalled {
+        platform,
+        path: &installed_file_path,
+    });
+
+    Ok(installed_file_path)
 }
 
-/// Builds the library and then watches for changes to the `src` directory of
-/// the Rust library, rebuilding when changes happen.
+/// Points the entry for a platform in the gdnlib file to a dynamic library.
+/// Libraries that don't have a gdnlib file, like GDExtension libraries, are
+/// left alone.
 ///
 /// # Arguments
 ///
-/// `rust_library_name`             - The name of the Rust library.
 /// `godot_project_absolute_path`   - The absolute path to the Godot project.
-/// `platform`                      - The platform to target the build for.
-/// `is_release`                    - Indicates whether the build is a release build or not. This is passed in by the user as an argument to the `build` command.
-/// `is_plugin`                     - Indicates whether the Godot project is a plugin or not.
-pub fn build_and_watch_for_changes(
-    rust_library_name: &str,
+/// `godot_project_bin_path`        - The path to the directory that the dynamic library is in.
+/// `rust_library_name`             - The name of the Rust library.
+/// `platform`                      - The platform to point the entry of.
+/// `dynamic_library_file_path`     - The path to the dynamic library that Godot should load.
+fn use_dynamic_library_in_gdnlib(
     godot_project_absolute_path: &str,
+    godot_project_bin_path: &Path,
+    rust_library_name: &str,
     platform: &str,
-    is_release: bool,
-    is_plugin: bool,
-) {
-    // Create our sender and receiver and then run the initial build of the
-    // Rust library.
-    let (tx, rx) = channel();
-    build_for_platform(
-        rust_library_name,
-        godot_project_absolute_path,
-        platform,
-        is_release,
-        is_plugin,
-    );
+    dynamic_library_file_path: &Path,
+) -> Result<()> {
+    // The gdnlib file is in the gdnative directory that the bin directory is
+    // in.
+    let gdnlib_file_path = match godot_project_bin_path.parent().and_then(Path::parent) {
+        Some(gdnative_dir) => gdnative_dir.join(format!("{}.gdnlib", rust_library_name)),
+        None => return Ok(()),
+    };
+    if !gdnlib_file_path.exists() {
+        return Ok(());
+    }
 
-    let mut last_checked = Local::now();
-    let mut watcher =
-        raw_watcher(tx).expect("Unable to create watcher to watch the Rust library for changes");
-    let current_dir = std::env::current_dir().expect(
-        "Unable to get current directory while attempting to watch the Rust library for changes",
+    let res_path = format!(
+        "res://{}",
+        get_godot_res_path(
+            Path::new(godot_project_absolute_path),
+            dynamic_library_file_path
+        )
     );
 
-    // Tell the watcher to watch the the `src` directory in the Rust library
-    // for changes.
-    watcher
-        .watch(current_dir.join("src"), RecursiveMode::Recursive)
-        .expect("Unable to watch the Rust library directory for changes");
+    Gdnlib::set_entry_in_file(&gdnlib_file_path, platform, &res_path)?;
+
+    Ok(())
+}
+
+/// How a build started by the watcher ended.
+enum WatchedBuild {
+    /// The build ran to the end, successfully or not.
+    Finished(Result<()>),
+    /// The build was cancelled because of a newer change to these paths.
+    Cancelled(Vec<PathBuf>),
+}
 
+/// Builds the library and then watches for changes to the library's `src`
+/// directory, its manifest and build script, and those of its local path
+/// dependencies, rebuilding when changes happen. Changes are debounced so
+/// that saving several files only triggers one build and a build that's
+/// still running when a newer change arrives is cancelled and restarted.
+/// The native platform is built first so that it can be used right away and
+/// then the other platforms are built in the background while the watching
+/// continues, so that a newer change cancels them instead of waiting for
+/// them to finish.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `native_platform` - The user's native platform.
+/// `other_platforms` - The other platforms to build for after the native one.
+/// `jobs` - The number of builds for the other platforms that can run at once.
+/// `delay` - How long to wait for changes to settle before rebuilding.
+pub fn build_and_watch_for_changes(
+    settings: &BuildSettings,
+    native_platform: &str,
+    other_platforms: &[String],
+    jobs: usize,
+    delay: Duration,
+) -> Result<()> {
+    // The builds are spawned in their own process groups so they have to be
+    // killed by the cli when it's interrupted.
+    kill_process_groups_on_interrupt()
+        .context("Unable to handle interrupts while watching the Rust library for changes")?;
+
+    let (tx, rx) = channel();
+    let mut watcher = watcher(tx, delay)
+        .context("Unable to create watcher to watch the Rust library for changes")?;
+
+    let watch_paths = get_watch_paths(settings.library_dir);
+    for watch_path in &watch_paths {
+        watcher
+            .watch(watch_path, RecursiveMode::Recursive)
+            .context(format!(
+                "Unable to watch {} for changes",
+                watch_path.display()
+            ))?;
+    }
+    log_info_to_console(&format!(
+        "[watch] watching {} paths for changes",
+        watch_paths.len()
+    ));
+
+    // The first build isn't triggered by any changes. A failed build
+    // shouldn't stop the watcher since the user is most likely in the middle
+    // of fixing it.
+    let mut changed_paths: Vec<PathBuf> = vec![];
+    let mut cycle = 0;
     loop {
-        // Whenever something happened to the `src` directory...
-        match rx.recv() {
-            Ok(RawEvent {
-                path: Some(_path),
-                op: Ok(op),
-                cookie: _,
-            }) => {
-                // Make sure that it's a write operation...
-                if op.contains(op::WRITE) {
-                    // Make sure that some time has passed since the last
-                    // build so that we don't spam it.
-                    let now = Local::now();
-                    if (now - last_checked).num_seconds() == 0 {
-                        // Run the build for the native platform!
-                        build_for_platform(
-                            rust_library_name,
-                            godot_project_absolute_path,
-                            platform,
-                            is_release,
-                            is_plugin,
-                        );
+        cycle += 1;
+        let trigger = if changed_paths.is_empty() {
+            "the initial build".to_string()
+        } else {
+            describe_changed_paths(settings.library_dir, &changed_paths)
+        };
+        log_info_to_console(&format!("[watch] cycle {} ({})", cycle, trigger));
+
+        let started_at = Instant::now();
+        let outcome = run_watched_build(settings, native_platform, &rx)?;
+        let elapsed = started_at.elapsed().as_secs_f32();
+
+        match outcome {
+            WatchedBuild::Finished(Ok(())) => {
+                log_success_to_console(&format!(
+                    "[watch] cycle {} {}: build succeeded in {:.2}s",
+                    cycle, native_platform, elapsed
+                ));
+
+                changed_paths = if other_platforms.is_empty() {
+                    wait_for_changes(&rx)?
+                } else {
+                    run_background_builds(settings, other_platforms, jobs, cycle, &rx)?
+                };
+            }
+            WatchedBuild::Finished(Err(e)) => {
+                log_error_to_console(&e.to_string());
+                log_error_to_console(&format!(
+                    "[watch] cycle {} {}: build failed after {:.2}s",
+                    cycle, native_platform, elapsed
+                ));
+
+                // The other platforms would fail the same way if the native
+                // build failed.
+                changed_paths = wait_for_changes(&rx)?;
+            }
+            WatchedBuild::Cancelled(newer_changed_paths) => {
+                log_info_to_console(&format!(
+                    "[watch] cycle {} {}: build cancelled after {:.2}s by a newer change",
+                    cycle, native_platform, elapsed
+                ));
+
+                // The newer change starts the next build right away.
+                changed_paths = newer_changed_paths;
+            }
+        }
+    }
+}
+
+/// Builds the library for the other platforms on a worker while waiting for
+/// the next change, which cancels the builds that are still running. A
+/// summary of the builds is logged if they all finish before the change.
+/// Returns the paths that changed.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `other_platforms` - The platforms to build for.
+/// `jobs` - The number of builds that can run at once.
+/// `cycle` - The watcher's cycle that the builds are part of.
+/// `rx` - The receiver for the watcher's events.
+fn run_background_builds(
+    settings: &BuildSettings,
+    other_platforms: &[String],
+    jobs: usize,
+    cycle: u32,
+    rx: &Receiver<DebouncedEvent>,
+) -> Result<Vec<PathBuf>> {
+    let logs_dir = create_build_logs_dir(settings)?;
+
+    // Only the native platform's dynamic library is versioned.
+    let process_groups = ProcessGroups::default();
+    let other_settings = BuildSettings {
+        is_versioned: false,
+        process_groups: Some(&process_groups),
+        ..*settings
+    };
+    let builds: Vec<(&BuildSettings, &str)> = other_platforms
+        .iter()
+        .map(|platform| (&other_settings, platform.as_str()))
+        .collect();
+
+    log_info_to_console(&format!(
+        "[watch] cycle {}: building {} in the background",
+        cycle,
+        other_platforms.join(", ")
+    ));
+
+    thread::scope(|scope| {
+        let worker = scope.spawn(|| {
+            let platform_builds = run_logged_builds(&builds, jobs, &logs_dir);
+
+            // The builds that were cancelled fail so there's nothing to sum
+            // up about them.
+            if !process_groups.is_killed() {
+                log_build_summary(settings, &logs_dir, platform_builds.iter());
+            }
+        });
+
+        let changed_paths = wait_for_changes(rx);
+        if !worker.is_finished() {
+            log_info_to_console(&format!(
+                "[watch] cycle {}: builds for the other platforms cancelled by a newer change",
+                cycle
+            ));
+            process_groups.kill_all();
+        }
+        let _ = worker.join();
+
+        changed_paths
+    })
+}
+
+/// Runs a build for the watcher, cancelling it if a change arrives before it
+/// finishes. The build runs in its own process group so that cancelling it
+/// also kills the processes that it started.
+///
+/// # Arguments
+///
+/// `settings` - The settings to build the library with.
+/// `platform` - The platform to target the build for.
+/// `rx` - The receiver for the watcher's events.
+fn run_watched_build(
+    settings: &BuildSettings,
+    platform: &str,
+    rx: &Receiver<DebouncedEvent>,
+) -> Result<WatchedBuild> {
+    let process_groups = ProcessGroups::default();
+    let settings = BuildSettings {
+        process_groups: Some(&process_groups),
+        ..*settings
+    };
+
+    thread::scope(|scope| {
+        let build = scope.spawn(|| build_for_platform(&settings, platform));
+        let join_build = |build: thread::ScopedJoinHandle<Result<()>>| {
+            build.join().unwrap_or_else(|_| {
+                Err(Error::Invalid(format!(
+                    "The build for {} stopped unexpectedly",
+                    platform
+                )))
+            })
+        };
+
+        loop {
+            if build.is_finished() {
+                return Ok(WatchedBuild::Finished(join_build(build)));
+            }
+
+            // Check for changes while the build runs so that it can be
+            // cancelled as soon as a newer change arrives.
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(event) => {
+                    if let Some(changed_path) = get_changed_path(event) {
+                        process_groups.kill_all();
+                        let _ = join_build(build);
+
+                        let mut changed_paths = vec![changed_path];
+                        changed_paths.extend(rx.try_iter().filter_map(get_changed_path));
+                        return Ok(WatchedBuild::Cancelled(changed_paths));
                     }
-                    last_checked = Local::now();
+                }
+                Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => {
+                    process_groups.kill_all();
+                    let _ = join_build(build);
+                    return Err(Error::Invalid(
+                        "The watcher stopped while watching the Rust library for changes"
+                            .to_string(),
+                    ));
                 }
             }
-            Ok(_event) => log_error_to_console(
-                "Broken event when trying to watch the Rust library for changes",
-            ),
-            Err(e) => log_error_to_console(&e.to_string()),
         }
+    })
+}
+
+/// Waits for the next change to the watched paths and returns the paths
+/// that changed.
+///
+/// # Arguments
+///
+/// `rx` - The receiver for the watcher's events.
+fn wait_for_changes(rx: &Receiver<DebouncedEvent>) -> Result<Vec<PathBuf>> {
+    loop {
+        let event = rx.recv().map_err(|_| {
+            Error::Invalid(
+                "The watcher stopped while watching the Rust library for changes".to_string(),
+            )
+        })?;
+
+        if let Some(changed_path) = get_changed_path(event) {
+            // Changes to several files are often debounced at the same time
+            // so we give them a moment to arrive and build for all of them.
+            thread::sleep(Duration::from_millis(50));
+
+            let mut changed_paths = vec![changed_path];
+            changed_paths.extend(rx.try_iter().filter_map(get_changed_path));
+            return Ok(changed_paths);
+        }
+    }
+}
+
+/// Returns the path that changed if an event from the watcher is a change
+/// that should trigger a build. Errors from the watcher are logged.
+///
+/// # Arguments
+///
+/// `event` - The event from the watcher.
+fn get_changed_path(event: DebouncedEvent) -> Option<PathBuf> {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Remove(path)
+        | DebouncedEvent::Rename(_, path) => Some(path),
+        DebouncedEvent::Error(e, _) => {
+            log_warning_to_console(&format!(
+                "Error while watching the Rust library for changes: {}",
+                e
+            ));
+            None
+        }
+        DebouncedEvent::NoticeWrite(_)
+        | DebouncedEvent::NoticeRemove(_)
+        | DebouncedEvent::Chmod(_)
+        | DebouncedEvent::Rescan => None,
+    }
+}
+
+/// Returns a description of the paths that changed for the summary of a
+/// watch cycle, relative to the library's directory.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `changed_paths` - The paths that changed.
+fn describe_changed_paths(library_dir: &Path, changed_paths: &[PathBuf]) -> String {
+    let mut unique_paths: Vec<String> = changed_paths
+        .iter()
+        .map(|changed_path| {
+            changed_path
+                .strip_prefix(library_dir)
+                .unwrap_or(changed_path)
+                .display()
+                .to_string()
+        })
+        .collect();
+    unique_paths.sort();
+    unique_paths.dedup();
+
+    match unique_paths.len() {
+        1 => format!("{} changed", unique_paths[0]),
+        count => format!("{} and {} other files changed", unique_paths[0], count - 1),
     }
 }
+
+/// Returns the paths that the watcher watches for changes to the library.
+/// These are the `src` directory, the manifest, and the build script of the
+/// library and of every local path dependency that it has. Only paths that
+/// exist are returned.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+pub fn get_watch_paths(library_dir: &Path) -> Vec<PathBuf> {
+    let mut watch_paths: Vec<PathBuf> = vec![];
+    let mut visited_dirs: HashSet<PathBuf> = HashSet::new();
+    let mut dirs_to_visit: Vec<PathBuf> = vec![library_dir.to_path_buf()];
+
+    while let Some(crate_dir) = dirs_to_visit.pop() {
+        let canonical_crate_dir = dunce::canonicalize(&crate_dir).unwrap_or(crate_dir.clone());
+        if !visited_dirs.insert(canonical_crate_dir) {
+            continue;
+        }
+
+        watch_paths.extend(
+            ["src", "Cargo.toml", "build.rs"]
+                .iter()
+                .map(|file_name| crate_dir.join(file_name))
+                .filter(|watch_path| watch_path.exists()),
+        );
+
+        dirs_to_visit.extend(
+            get_path_dependencies(&crate_dir.join("Cargo.toml"))
+                .into_iter()
+                .map(|dependency_path| crate_dir.join(dependency_path)),
+        );
+    }
+
+    watch_paths
+}
+
+/// Returns the paths of the local path dependencies in a manifest, relative
+/// to the manifest's directory. This includes the regular, build, dev, target
+/// specific, and workspace dependencies.
+///
+/// # Arguments
+///
+/// `manifest_path` - The path to the Cargo.toml file.
+fn get_path_dependencies(manifest_path: &Path) -> Vec<PathBuf> {
+    let manifest: toml::Value = match read_to_string(manifest_path)
+        .ok()
+        .and_then(|manifest_string| toml::from_str(&manifest_string).ok())
+    {
+        Some(manifest) => manifest,
+        None => return vec![],
+    };
+
+    let mut dependency_tables: Vec<&toml::Value> = vec![];
+    for table_name in &["dependencies", "build-dependencies", "dev-dependencies"] {
+        dependency_tables.extend(manifest.get(table_name));
+        dependency_tables.extend(
+            manifest
+                .get("workspace")
+                .and_then(|workspace| workspace.get(table_name)),
+        );
+        if let Some(toml::Value::Table(targets)) = manifest.get("target") {
+            dependency_tables.extend(targets.values().filter_map(|target| target.get(table_name)));
+        }
+    }
+
+    dependency_tables
+        .into_iter()
+        .filter_map(|dependency_table| dependency_table.as_table())
+        .flat_map(|dependency_table| dependency_table.values())
+        .filter_map(|dependency| dependency.get("path").and_then(|path| path.as_str()))
+        .map(PathBuf::from)
+        .collect()
+}
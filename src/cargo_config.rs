@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
 use std::fs::read_to_string;
-use std::{env::current_dir, fs::create_dir_all, process::exit};
+use std::path::PathBuf;
+use std::{fs::create_dir_all, path::Path};
 
-use crate::log_utils::log_error_to_console;
+use crate::error::{Context, Result};
 
 /// The structure of the config.toml file for the Rust library.
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,50 +35,101 @@ impl CargoConfig {
         }
     }
 
-    /// Returns the Config as a pretty printed toml string.
+    /// Reads the contents of the `.cargo/config.toml` file and returns it.
     ///
     /// # Arguments
     ///
-    /// `self` - The current Config object.
-    pub fn to_string(&mut self) -> String {
-        toml::to_string_pretty(self).expect("Unable to convert config to string")
-    }
-
-    /// Reads the contents of the `.cargo/config.toml` file and returns it.
-    pub fn read() -> CargoConfig {
+    /// `library_dir` - The path to the library's directory.
+    pub fn read(library_dir: &Path) -> Result<CargoConfig> {
         // The path to the `.cargo/config.toml` file.
-        let current_dir = current_dir().expect("Unable to run build, please try again");
-        let config_file_path = current_dir.join(".cargo").join("config.toml");
+        let config_file_path = library_dir.join(".cargo").join("config.toml");
 
         // Read the contents of the config file to a string.
         let rust_library_config_toml_string = read_to_string(config_file_path)
-            .expect("Unable to read the Rust library's config.toml file");
+            .context("Unable to read the Rust library's config.toml file")?;
 
         // Next, we deserialize it into our structure.
-        let rust_library_config_toml: CargoConfig =
-            toml::from_str(&rust_library_config_toml_string)
-                .expect("Unable to parse the Rust library's config.toml file");
-
-        rust_library_config_toml
+        toml::from_str(&rust_library_config_toml_string)
+            .context("Unable to parse the Rust library's config.toml file")
     }
 
     /// Writes the provided Config to the `.cargo/config.toml` file.
-    pub fn write(&mut self) {
+    ///
+    /// # Arguments
+    ///
+    /// `library_dir` - The path to the library's directory.
+    pub fn write(&self, library_dir: &Path) -> Result<()> {
         // The path to the `.cargo` directory.
-        let current_dir = current_dir().expect("Unable to run build, please try again");
-        let dotcargo_dir = current_dir.join(".cargo");
+        let dotcargo_dir = library_dir.join(".cargo");
 
         // If this is the first time that the file is being written it might
         // not exist so we have to make sure to create the `.cargo` directory.
-        match create_dir_all(&dotcargo_dir) {
-            Ok(_) => (),
-            Err(e) => {
-                log_error_to_console(&e.to_string());
-                exit(1);
-            }
-        }
+        create_dir_all(&dotcargo_dir).context("Unable to create the .cargo directory")?;
 
         std::fs::write(dotcargo_dir.join("config.toml"), self.to_string())
-            .expect("Unable to update contents of the Rust library's config.toml file");
+            .context("Unable to update contents of the Rust library's config.toml file")
     }
 }
+
+impl fmt::Display for CargoConfig {
+    /// Writes the Config as a pretty printed toml string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let config_string =
+            toml::to_string_pretty(self).expect("Unable to convert config to string");
+
+        write!(f, "{}", config_string)
+    }
+}
+
+/// Returns the linker that cargo uses for a target when building the
+/// library, if one is configured. Like cargo, the `CARGO_TARGET_<TRIPLE>_LINKER`
+/// environment variable is checked first and then the `linker` under
+/// `[target.<triple>]` in the config files of the library's directory, its
+/// parent directories, and cargo's home directory.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `rust_triple` - The target to find the linker for.
+pub fn find_target_linker(library_dir: &Path, rust_triple: &str) -> Option<String> {
+    let linker_env_var = format!(
+        "CARGO_TARGET_{}_LINKER",
+        rust_triple.to_uppercase().replace('-', "_")
+    );
+    if let Some(linker) = env::var(linker_env_var)
+        .ok()
+        .filter(|linker| !linker.is_empty())
+    {
+        return Some(linker);
+    }
+
+    let cargo_home_dir = env::var_os("CARGO_HOME").map(PathBuf::from).or_else(|| {
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home_dir| PathBuf::from(home_dir).join(".cargo"))
+    });
+    let config_file_paths = library_dir
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .chain(cargo_home_dir)
+        .flat_map(|dir| vec![dir.join("config.toml"), dir.join("config")]);
+
+    for config_file_path in config_file_paths {
+        let linker = read_to_string(&config_file_path)
+            .ok()
+            .and_then(|contents| toml::from_str::<toml::Value>(&contents).ok())
+            .and_then(|config| {
+                config
+                    .get("target")?
+                    .get(rust_triple)?
+                    .get("linker")?
+                    .as_str()
+                    .map(str::to_string)
+            });
+        if linker.is_some() {
+            return linker;
+        }
+    }
+
+    None
+}
@@ -14,13 +14,16 @@ pub struct CargoToml {
     pub dependencies: CargoDependencies,
 }
 
-/// The fields of the Cargo.toml that are under the [package] tag.
+/// The fields of the Cargo.toml that are under the [package] tag. The fields
+/// other than the name can be inherited from the workspace, like
+/// `edition.workspace = true`, so they're kept as whatever toml value they
+/// have.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CargoPackage {
     pub name: String,
-    pub version: String,
-    pub authors: Option<Vec<String>>,
-    pub edition: String,
+    pub version: toml::Value,
+    pub authors: Option<toml::Value>,
+    pub edition: toml::Value,
 }
 
 /// The fields of the Cargo.toml that are under the [lib] tag.
@@ -33,22 +36,33 @@ pub struct CargoLib {
 /// The fields of the Cargo.toml that are under the [dependencies] tag.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CargoDependencies {
-    #[serde(default = "add_gdnative_dep")]
-    pub gdnative: String,
+    /// The gdnative dependency used by GDNative libraries.
+    #[serde(default = "add_gdnative_dep", skip_serializing_if = "Option::is_none")]
+    pub gdnative: Option<String>,
+    /// The godot dependency used by GDExtension libraries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub godot: Option<String>,
 }
 
 /// Returns the contents of what should appear under the [lib] tag. This is
 /// used by the Cargo struct to create the default value for the [lib] tag
 /// if no value is present.
 fn create_cargo_lib() -> CargoLib {
-    return CargoLib {
+    CargoLib {
         crate_type: vec!["cdylib".to_string()],
-    };
+    }
 }
 
 /// Returns the gdnative dependency to add to the Cargo.toml dependencies. This
 /// is used by the CargoDependencies struct to add the gdnative dependency that
 /// is necessary.
-fn add_gdnative_dep() -> String {
-    return String::from("0.9.3");
+fn add_gdnative_dep() -> Option<String> {
+    Some(String::from("0.9.3"))
+}
+
+/// Returns the godot dependency to add to the Cargo.toml dependencies. This
+/// is used instead of the gdnative dependency when the library is created for
+/// GDExtension.
+pub fn add_godot_dep() -> Option<String> {
+    Some(String::from("0.2.4"))
 }
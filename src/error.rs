@@ -0,0 +1,177 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+
+/// The result of an operation that can fail with an [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The errors that can happen while managing a library.
+///
+/// Each kind of error has its own exit code so that scripts using the cli can
+/// tell them apart:
+///
+/// | Exit code | Error                           |
+/// |-----------|---------------------------------|
+/// | 1         | Any other error                 |
+/// | 2         | Config missing                  |
+/// | 3         | Godot project invalid           |
+/// | 4         | Cargo failed                    |
+/// | 5         | Cross failed                    |
+/// | 6         | Artifact missing or copy failed |
+#[derive(Debug)]
+pub enum Error {
+    /// The godot-rust-cli.json config file doesn't exist, which usually means
+    /// that the command wasn't run from the library directory.
+    ConfigMissing(PathBuf),
+    /// The directory isn't a Godot project because it doesn't have a
+    /// project.godot file.
+    GodotProjectInvalid(PathBuf),
+    /// Cargo couldn't be run or exited with a failure.
+    CargoFailed {
+        /// What cargo was being run for.
+        message: String,
+        /// The status cargo exited with, if it could be run.
+        status: Option<ExitStatus>,
+        /// The error from trying to run cargo, if it couldn't be run.
+        source: Option<io::Error>,
+    },
+    /// Cross couldn't be run or exited with a failure.
+    CrossFailed {
+        /// What cross was being run for.
+        message: String,
+        /// The status cross exited with, if it could be run.
+        status: Option<ExitStatus>,
+        /// The error from trying to run cross, if it couldn't be run.
+        source: Option<io::Error>,
+    },
+    /// The dynamic library wasn't where the build was expected to put it.
+    ArtifactMissing(PathBuf),
+    /// The build succeeded without reporting a dynamic library for the
+    /// library.
+    ArtifactNotReported {
+        /// The name of the library.
+        name: String,
+        /// The crate type that the build was expected to report.
+        crate_type: &'static str,
+    },
+    /// A built dynamic library couldn't be copied to the Godot project.
+    CopyFailed {
+        /// The path to the dynamic library.
+        from: PathBuf,
+        /// The path that the dynamic library was being copied to.
+        to: PathBuf,
+        /// The error from copying the dynamic library.
+        source: io::Error,
+    },
+    /// The input to a command was invalid, like a module that already exists.
+    Invalid(String),
+    /// Any other error along with what was being done when it happened.
+    Other {
+        /// What was being done when the error happened.
+        message: String,
+        /// The error that happened.
+        source: Box<dyn StdError + Send + Sync>,
+    },
+}
+
+impl Error {
+    /// Returns the exit code that the cli exits with for the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::ConfigMissing(_) => 2,
+            Error::GodotProjectInvalid(_) => 3,
+            Error::CargoFailed { .. } => 4,
+            Error::CrossFailed { .. } => 5,
+            Error::ArtifactMissing(_)
+            | Error::ArtifactNotReported { .. }
+            | Error::CopyFailed { .. } => 6,
+            Error::Invalid(_) | Error::Other { .. } => 1,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    /// Writes the error as a message that can be shown to the user. The
+    /// causes of the error are not included and can be found with `source`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ConfigMissing(path) => write!(
+                f,
+                "Unable to find the config file at {}, this command must be used from the library directory",
+                path.display()
+            ),
+            Error::GodotProjectInvalid(path) => write!(
+                f,
+                "{} isn't a valid Godot project because it doesn't have a project.godot file",
+                path.display()
+            ),
+            Error::CargoFailed {
+                message, status, ..
+            }
+            | Error::CrossFailed {
+                message, status, ..
+            } => match status {
+                Some(status) => write!(f, "{} ({})", message, status),
+                None => write!(f, "{}", message),
+            },
+            Error::ArtifactMissing(path) => write!(
+                f,
+                "Unable to find the built dynamic library at {}",
+                path.display()
+            ),
+            Error::ArtifactNotReported { name, crate_type } => write!(
+                f,
+                "The build didn't report a dynamic library for {}, make sure that its crate-type includes {}",
+                name, crate_type
+            ),
+            Error::CopyFailed { from, to, .. } => write!(
+                f,
+                "Unable to copy {} to {}",
+                from.display(),
+                to.display()
+            ),
+            Error::Invalid(message) | Error::Other { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::CargoFailed { source, .. } | Error::CrossFailed { source, .. } => {
+                source.as_ref().map(|e| e as &(dyn StdError + 'static))
+            }
+            Error::CopyFailed { source, .. } => Some(source),
+            Error::Other { source, .. } => Some(source.as_ref()),
+            Error::ConfigMissing(_)
+            | Error::GodotProjectInvalid(_)
+            | Error::ArtifactMissing(_)
+            | Error::ArtifactNotReported { .. }
+            | Error::Invalid(_) => None,
+        }
+    }
+}
+
+/// Adds a message describing what was being done to the error of a result.
+pub trait Context<T> {
+    /// Wraps the error in an [`Error::Other`] with the provided message.
+    ///
+    /// # Arguments
+    ///
+    /// `message` - What was being done when the error happened.
+    fn context<S: Into<String>>(self, message: S) -> Result<T>;
+}
+
+impl<T, E> Context<T> for std::result::Result<T, E>
+where
+    E: StdError + Send + Sync + 'static,
+{
+    fn context<S: Into<String>>(self, message: S) -> Result<T> {
+        self.map_err(|source| Error::Other {
+            message: message.into(),
+            source: Box::new(source),
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use crate::platforms::PLATFORMS;
+
+/// The structure of the gdextension file that Godot 4 uses to load the
+/// library.
+#[derive(Debug)]
+pub struct GdExtension {
+    /// The name of the symbol that Godot calls to initialize the library.
+    pub entry_symbol: String,
+    /// The minimum version of Godot that the library can be loaded by.
+    pub compatibility_minimum: String,
+    /// Indicates whether Godot can hot reload the library or not.
+    pub reloadable: bool,
+    /// The feature tags and the paths to the binaries they load.
+    pub libraries: Vec<(String, String)>,
+}
+
+impl GdExtension {
+    /// Creates a new instance of the GdExtension with the default properties.
+    ///
+    /// # Arguments
+    ///
+    /// `rust_library_name_normalized`  - The snake_case version of the Rust library name.
+    pub fn new(rust_library_name_normalized: &str) -> GdExtension {
+        // Defines the path to the binary of every platform that the library
+        // can be built for. Just like with the gdnlib file, Godot doesn't
+        // complain if they're not being used. The debug and release feature
+        // tags point to the same binary since the build copies whichever one
+        // was built.
+        let mut libraries: Vec<(String, String)> = vec![];
+        for build in ["debug", "release"] {
+            for platform in PLATFORMS {
+                let feature_tag = platform.get_gdextension_feature_tag(build);
+                if libraries
+                    .iter()
+                    .any(|(library_tag, _)| library_tag == &feature_tag)
+                {
+                    continue;
+                }
+
+                libraries.push((
+                    feature_tag,
+                    format!(
+                        "res://gdnative/bin/{}/{}",
+                        platform.bin_dir,
+                        platform.get_library_filename(rust_library_name_normalized)
+                    ),
+                ));
+            }
+        }
+
+        GdExtension {
+            entry_symbol: "gdext_rust_init".to_string(),
+            compatibility_minimum: "4.1".to_string(),
+            reloadable: true,
+            libraries,
+        }
+    }
+}
+
+impl fmt::Display for GdExtension {
+    /// Writes the GdExtension in the config file format that Godot expects.
+    /// This isn't written with toml because Godot expects the dotted feature
+    /// tags to be bare keys.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[configuration]")?;
+        writeln!(f)?;
+        writeln!(f, "entry_symbol = \"{}\"", self.entry_symbol)?;
+        writeln!(f, "compatibility_minimum = {}", self.compatibility_minimum)?;
+        writeln!(f, "reloadable = {}", self.reloadable)?;
+        writeln!(f)?;
+        writeln!(f, "[libraries]")?;
+        writeln!(f)?;
+        for (feature_tag, bin_path) in &self.libraries {
+            writeln!(f, "{} = \"{}\"", feature_tag, bin_path)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,13 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Context, Error, Result};
+use crate::platforms::{get_platform, PLATFORMS};
+
 /// The structure of the gdnlib file.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Gdnlib {
@@ -37,50 +43,33 @@ impl Gdnlib {
             "res:/".to_owned()
         };
 
-        // Defines the path to the various popular binaries that could exist.
-        // We're allowed to define them all here as Godot (currently) doesn't
-        // complain if they're not being used.
-        let osx_bin_path = format!(
-            "{}/gdnative/bin/macos/lib{}.dylib",
-            gdnlib_base_path, rust_library_name_normalized
-        );
-        let windows_bin_path = format!(
-            "{}/gdnative/bin/windows/{}.dll",
-            gdnlib_base_path, rust_library_name_normalized
-        );
-        let linux_bin_path = format!(
-            "{}/gdnative/bin/linux/lib{}.so",
-            gdnlib_base_path, rust_library_name_normalized
-        );
-        let android_arm_bin_path = format!(
-            "{}/gdnative/bin/android/aarch64-linux-android/lib{}.so",
-            gdnlib_base_path, rust_library_name_normalized
-        );
-        let android_64_bin_path = format!(
-            "{}/gdnative/bin/android/x86_64-linux-android/lib{}.so",
-            gdnlib_base_path, rust_library_name_normalized
-        );
-
-        // The locations to the binaries above are inserted into a `HashMap`
-        // which is then used as the contents under the `[entries]` tag in the
-        // gdnlib file.
-        let mut entries: HashMap<String, String> = HashMap::new();
-        entries.insert("OSX.64".to_owned(), osx_bin_path);
-        entries.insert("Windows.64".to_owned(), windows_bin_path);
-        entries.insert("X11.64".to_owned(), linux_bin_path);
-        entries.insert("Android.arm64-v8a".to_owned(), android_arm_bin_path);
-        entries.insert("Android.x86_64".to_owned(), android_64_bin_path);
+        // Defines the path to the binary of every platform that the library
+        // can be built for. We're allowed to define them all here as Godot
+        // (currently) doesn't complain if they're not being used. These are
+        // used as the contents under the `[entries]` tag in the gdnlib file.
+        let entries: HashMap<String, String> = PLATFORMS
+            .iter()
+            .map(|platform| {
+                (
+                    platform.gdnative_feature_tag.to_owned(),
+                    format!(
+                        "{}/gdnative/bin/{}/{}",
+                        gdnlib_base_path,
+                        platform.bin_dir,
+                        platform.get_library_filename(rust_library_name_normalized)
+                    ),
+                )
+            })
+            .collect();
 
         // Another tag we have to create the content for is the
-        // `[dependencies]` tag. Here we define the same popular operating
-        // systems as above and set them to an empty array since we don't
-        // start with any dependencies yet.
-        let mut deps: HashMap<String, Vec<String>> = HashMap::new();
-        deps.insert("OSX.64".to_owned(), vec![]);
-        deps.insert("Windows.64".to_owned(), vec![]);
-        deps.insert("X11.64".to_owned(), vec![]);
-        deps.insert("Android.arm64-v8a".to_owned(), vec![]);
-        deps.insert("Android.x86_64".to_owned(), vec![]);
+        // `[dependencies]` tag. Here we define the same platforms as above and
+        // set them to an empty array since we don't start with any
+        // dependencies yet.
+        let deps: HashMap<String, Vec<String>> = PLATFORMS
+            .iter()
+            .map(|platform| (platform.gdnative_feature_tag.to_owned(), vec![]))
+            .collect();
 
         Gdnlib {
             general: GdnlibGeneral {
@@ -94,17 +83,126 @@ impl Gdnlib {
         }
     }
 
-    /// Returns the Gdnlib as a pretty printed toml string.
+    /// Returns the name of the entry in the gdnlib file for a platform, if
+    /// the platform has one.
+    ///
+    /// # Arguments
+    ///
+    /// `platform` - The platform to get the entry name of.
+    pub fn get_entry_name(platform: &str) -> Option<&'static str> {
+        get_platform(platform).map(|platform| platform.gdnative_feature_tag)
+    }
+
+    /// Points the entry for a platform in a gdnlib file to a dynamic library
+    /// and returns whether the entry was changed. Only the line of the entry
+    /// is rewritten since Godot saves the file in its own format, with keys
+    /// like `X11.64` left bare, which a toml parser doesn't read back as the
+    /// same keys.
     ///
     /// # Arguments
     ///
-    /// `self` - The current Gdnlib object.
-    pub fn to_string(&mut self) -> String {
+    /// `path`      - The path to the gdnlib file.
+    /// `platform`  - The platform to set the entry of.
+    /// `res_path`  - The `res://` path to the dynamic library.
+    pub fn set_entry_in_file(path: &Path, platform: &str, res_path: &str) -> Result<bool> {
+        let entry_name = match Gdnlib::get_entry_name(platform) {
+            Some(entry_name) => entry_name,
+            None => return Ok(false),
+        };
+
+        let gdnlib_string =
+            read_to_string(path).context("Unable to read the Godot project's gdnlib file")?;
+
+        match set_entry_in_string(&gdnlib_string, entry_name, res_path)? {
+            Some(updated_gdnlib_string) => {
+                std::fs::write(path, updated_gdnlib_string)
+                    .context("Unable to update contents of the Godot project's gdnlib file")?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// Returns the contents of a gdnlib file with an entry pointed to a dynamic
+/// library, or `None` if the entry already points to it. The entry is added
+/// to the end of the `[entry]` section if it doesn't exist yet.
+///
+/// # Arguments
+///
+/// `gdnlib_string` - The contents of the gdnlib file.
+/// `entry_name`    - The name of the entry to set.
+/// `res_path`      - The `res://` path to the dynamic library.
+fn set_entry_in_string(
+    gdnlib_string: &str,
+    entry_name: &str,
+    res_path: &str,
+) -> Result<Option<String>> {
+    let mut lines: Vec<String> = gdnlib_string
+        .split_inclusive('\n')
+        .map(str::to_owned)
+        .collect();
+
+    let mut is_in_entry_section = false;
+    let mut last_entry_line_index = None;
+    for (index, line) in lines.iter_mut().enumerate() {
+        let trimmed_line = line.trim();
+        if trimmed_line.starts_with('[') {
+            is_in_entry_section = trimmed_line == "[entry]";
+            if is_in_entry_section {
+                last_entry_line_index = Some(index);
+            }
+            continue;
+        }
+        if !is_in_entry_section || trimmed_line.is_empty() || trimmed_line.starts_with(';') {
+            continue;
+        }
+        last_entry_line_index = Some(index);
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key, value),
+            None => continue,
+        };
+        if key.trim().trim_matches('"') != entry_name {
+            continue;
+        }
+        if value.trim().trim_matches('"') == res_path {
+            return Ok(None);
+        }
+
+        // The key and the space around the `=` are kept as they are so that
+        // only the path changes.
+        let value_indent = &value[..value.len() - value.trim_start().len()];
+        let line_ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+        *line = format!("{}={}\"{}\"{}", key, value_indent, res_path, line_ending);
+
+        return Ok(Some(lines.concat()));
+    }
+
+    let last_entry_line_index = last_entry_line_index.ok_or_else(|| {
+        Error::Invalid("The Godot project's gdnlib file doesn't have an entry section".to_owned())
+    })?;
+    if !lines[last_entry_line_index].ends_with('\n') {
+        lines[last_entry_line_index].push('\n');
+    }
+    lines.insert(
+        last_entry_line_index + 1,
+        format!("{}=\"{}\"\n", entry_name, res_path),
+    );
+
+    Ok(Some(lines.concat()))
+}
+
+impl fmt::Display for Gdnlib {
+    /// Writes the Gdnlib as a pretty printed toml string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // We have to replace single quotes with double quotes here as that is
         // what is expected by Godot.
         // TOOD: Replace the quotes earlier in the process.
-        toml::to_string_pretty(self)
+        let gdnlib_string = toml::to_string_pretty(self)
             .expect("Unable to convert gdnlib to string")
-            .replace("'", "\"")
+            .replace('\'', "\"");
+
+        write!(f, "{}", gdnlib_string)
     }
 }
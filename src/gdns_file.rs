@@ -1,5 +1,8 @@
+use std::fmt;
 use std::path::PathBuf;
 
+use crate::error::{Context, Result};
+
 /// The structure of a gdns file that is used to describe a script.
 #[derive(Debug)]
 pub struct GdnsFile {
@@ -24,9 +27,21 @@ impl GdnsFile {
         }
     }
 
-    /// Returns the GdnsFile as a pretty printed string.
-    pub fn to_string(&mut self) -> String {
-        let gdnlib_file_string = format!(
+    /// Writes the provided GdnsFile to the gdns file.
+    ///
+    /// # Arguments
+    ///
+    /// `path` - The path to write the file to.
+    pub fn write(&self, path: PathBuf) -> Result<()> {
+        std::fs::write(path, self.to_string()).context("Unable to update contents of the gdns file")
+    }
+}
+
+impl fmt::Display for GdnsFile {
+    /// Writes the GdnsFile as a pretty printed string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
             r#"[gd_resource type="NativeScript" load_steps=2 format=2]
 
 [ext_resource path="res://{}.gdnlib" type="GDNativeLibrary" id=1]
@@ -38,17 +53,6 @@ class_name = "{}"
 library = ExtResource( 1 )
 "#,
             self.gdnlib_path, self.name, self.name
-        );
-
-        gdnlib_file_string
-    }
-
-    /// Writes the provided GdnsFile to the gdns file.
-    ///
-    /// # Arguments
-    ///
-    /// `path` - The path to write the file to.
-    pub fn write(&mut self, path: PathBuf) {
-        std::fs::write(path, self.to_string()).expect("Unable to update contents of the gdns file");
+        )
     }
 }
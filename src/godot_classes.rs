@@ -0,0 +1,254 @@
+use crate::config_utils::Api;
+
+/// The kinds of Godot classes that a module can inherit from. The kind
+/// decides which lifecycle methods are added to a new module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GodotClassKind {
+    /// A node that is processed every frame.
+    Node,
+    /// A physics body that is processed every physics frame.
+    PhysicsBody,
+    /// A UI node that receives gui input.
+    Control,
+    /// An editor plugin which only needs to be set up when it's ready.
+    EditorPlugin,
+    /// A resource or reference counted object that isn't in the scene tree
+    /// so it doesn't have any lifecycle methods.
+    Resource,
+}
+
+/// A Godot class that a module can inherit from.
+#[derive(Debug, Clone, Copy)]
+pub struct GodotClass {
+    /// The name of the class.
+    pub name: &'static str,
+    /// The kind of the class.
+    pub kind: GodotClassKind,
+    /// The Godot APIs that have the class.
+    pub apis: &'static [Api],
+}
+
+/// The classes that are only in Godot 3.
+const GDNATIVE: &[Api] = &[Api::Gdnative];
+/// The classes that are only in Godot 4.
+const GDEXTENSION: &[Api] = &[Api::Gdextension];
+/// The classes that are in both Godot 3 and Godot 4.
+const ALL_APIS: &[Api] = &[Api::Gdnative, Api::Gdextension];
+
+/// The Godot classes that modules can be created for. Classes that were
+/// renamed in Godot 4 have an entry for each name.
+pub const GODOT_CLASSES: &[GodotClass] = &[
+    GodotClass {
+        name: "Node",
+        kind: GodotClassKind::Node,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Node2D",
+        kind: GodotClassKind::Node,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Spatial",
+        kind: GodotClassKind::Node,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "Node3D",
+        kind: GodotClassKind::Node,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "CanvasLayer",
+        kind: GodotClassKind::Node,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Sprite",
+        kind: GodotClassKind::Node,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "Sprite2D",
+        kind: GodotClassKind::Node,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "Camera2D",
+        kind: GodotClassKind::Node,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Camera",
+        kind: GodotClassKind::Node,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "Camera3D",
+        kind: GodotClassKind::Node,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "Area2D",
+        kind: GodotClassKind::Node,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Area",
+        kind: GodotClassKind::Node,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "Area3D",
+        kind: GodotClassKind::Node,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "KinematicBody2D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "KinematicBody",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "CharacterBody2D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "CharacterBody3D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "RigidBody2D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "RigidBody",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "RigidBody3D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "StaticBody2D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "StaticBody",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "StaticBody3D",
+        kind: GodotClassKind::PhysicsBody,
+        apis: GDEXTENSION,
+    },
+    GodotClass {
+        name: "Control",
+        kind: GodotClassKind::Control,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Container",
+        kind: GodotClassKind::Control,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Panel",
+        kind: GodotClassKind::Control,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Label",
+        kind: GodotClassKind::Control,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Button",
+        kind: GodotClassKind::Control,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "EditorPlugin",
+        kind: GodotClassKind::EditorPlugin,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Resource",
+        kind: GodotClassKind::Resource,
+        apis: ALL_APIS,
+    },
+    GodotClass {
+        name: "Reference",
+        kind: GodotClassKind::Resource,
+        apis: GDNATIVE,
+    },
+    GodotClass {
+        name: "RefCounted",
+        kind: GodotClassKind::Resource,
+        apis: GDEXTENSION,
+    },
+];
+
+impl GodotClass {
+    /// Returns the class with the provided name if it's a known class for the
+    /// Godot API. The name is matched case insensitively.
+    ///
+    /// # Arguments
+    ///
+    /// `name` - The name of the class.
+    /// `api` - The Godot API that the class should be in.
+    pub fn find(name: &str, api: Api) -> Option<GodotClass> {
+        GODOT_CLASSES
+            .iter()
+            .find(|class| class.name.eq_ignore_ascii_case(name) && class.apis.contains(&api))
+            .copied()
+    }
+
+    /// Returns the default class for a new module, which is EditorPlugin for
+    /// plugin libraries and Node2D otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// `is_plugin` - Indicates whether the library is for a plugin or not.
+    pub fn default_for_library(is_plugin: bool) -> GodotClass {
+        let name = if is_plugin { "EditorPlugin" } else { "Node2D" };
+
+        GodotClass::find(name, Api::Gdnative).expect("Unable to find the default Godot class")
+    }
+
+    /// Returns the path of the module that the class is imported from.
+    ///
+    /// # Arguments
+    ///
+    /// `api` - The Godot API to import the class from.
+    pub fn import_path(api: Api) -> &'static str {
+        match api {
+            Api::Gdnative => "gdnative::api",
+            Api::Gdextension => "godot::classes",
+        }
+    }
+
+    /// Returns the names of the known classes for a Godot API.
+    ///
+    /// # Arguments
+    ///
+    /// `api` - The Godot API to get the classes of.
+    pub fn names_for_api(api: Api) -> Vec<&'static str> {
+        GODOT_CLASSES
+            .iter()
+            .filter(|class| class.apis.contains(&api))
+            .map(|class| class.name)
+            .collect()
+    }
+}
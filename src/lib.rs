@@ -0,0 +1,66 @@
+//! The core of godot-rust-cli which can be used to manage a library of Rust
+//! modules for a Godot project without going through the cli.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use godot_rust_cli::{BuildOptions, ModuleOptions, Project};
+//!
+//! let mut project = Project::open(Path::new("/games/platformer_modules"))?;
+//! project.create_module("Player", ModuleOptions::default())?;
+//! project.build(BuildOptions::default())?;
+//! # Ok::<(), godot_rust_cli::Error>(())
+//! ```
+
+mod build_utils;
+mod cargo_config;
+mod definitions;
+mod error;
+mod gdextension;
+mod gdnlib;
+mod gdns_file;
+mod godot_classes;
+pub mod log_utils;
+mod platforms;
+mod plugin_config;
+mod project;
+mod tscn_file;
+
+#[path = "./utils/android.rs"]
+mod android_utils;
+#[path = "./utils/config.rs"]
+mod config_utils;
+#[path = "./utils/cross.rs"]
+mod cross_utils;
+#[path = "./utils/doctor.rs"]
+mod doctor_utils;
+#[path = "./utils/emscripten.rs"]
+mod emscripten_utils;
+#[path = "./utils/file.rs"]
+mod file_utils;
+#[path = "./utils/install.rs"]
+mod install_utils;
+#[path = "./utils/lib.rs"]
+mod lib_utils;
+#[path = "./utils/macho.rs"]
+mod macho_utils;
+#[path = "./utils/path.rs"]
+mod path_utils;
+#[path = "./utils/process.rs"]
+mod process_utils;
+#[path = "./utils/scan.rs"]
+mod scan_utils;
+#[path = "./utils/template.rs"]
+mod template_utils;
+#[path = "./utils/workspace.rs"]
+mod workspace_utils;
+
+pub use config_utils::{Api, Config, PlatformBackend};
+pub use error::{Context, Error, Result};
+pub use gdnlib::{Gdnlib, GdnlibGeneral};
+pub use gdns_file::GdnsFile;
+pub use macho_utils::create_universal_binary;
+pub use plugin_config::{PluginConfig, PluginConfigPlugin};
+pub use project::{BuildOptions, ModuleOptions, NewOptions, Project};
@@ -1,4 +1,286 @@
 use colored::Colorize;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::env::var_os;
+use std::error::Error as StdError;
+use std::io::{stderr, stdin, stdout, IsTerminal, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use crate::error::Error;
+
+/// Indicates whether events are reported as json objects. This is set once
+/// when the cli starts.
+static IS_JSON_MESSAGE_FORMAT: AtomicBool = AtomicBool::new(false);
+
+/// How much is logged to the console. This is set once when the cli starts.
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+/// Indicates whether the messages written to stdout are coloured. This is set
+/// once when the cli starts.
+static IS_STDOUT_COLORED: AtomicBool = AtomicBool::new(true);
+
+/// Indicates whether the messages written to stderr are coloured. This is set
+/// once when the cli starts.
+static IS_STDERR_COLORED: AtomicBool = AtomicBool::new(true);
+
+/// The levels of how much is logged to the console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Only warnings and errors are logged.
+    Quiet,
+    /// Warnings, errors, and the progress of the command are logged.
+    #[default]
+    Normal,
+    /// The commands that are run, like cargo, cross, and docker, are logged
+    /// as well.
+    Verbose,
+    /// The directory that each command is run in is logged as well and cargo
+    /// is asked to log verbosely too.
+    VeryVerbose,
+}
+
+impl Verbosity {
+    /// Returns the verbosity for the number of times that `-v` was passed
+    /// and whether `-q` was passed.
+    ///
+    /// # Arguments
+    ///
+    /// `verbose` - The number of times that `-v` was passed.
+    /// `quiet` - Indicates whether `-q` was passed.
+    pub fn from_flags(verbose: u64, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::VeryVerbose,
+        }
+    }
+}
+
+/// The formats that the progress of a command can be reported in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MessageFormat {
+    /// Coloured messages for people written to stdout.
+    #[default]
+    Human,
+    /// One json object per event written to stdout, with the messages for
+    /// people written to stderr instead.
+    Json,
+}
+
+impl FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(message_format: &str) -> std::result::Result<Self, Self::Err> {
+        match message_format.to_lowercase().as_str() {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!(
+                "{} isn't a valid message format, expected human or json",
+                message_format
+            )),
+        }
+    }
+}
+
+/// The events that are reported while a command runs. With the json message
+/// format each event is written to stdout as a json object with an `event`
+/// field holding the snake_case name of the event.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A message about the progress of the command.
+    Info { message: &'a str },
+    /// A message about something that succeeded.
+    Success { message: &'a str },
+    /// A message about something that might be a problem.
+    Warning { message: &'a str },
+    /// A message about something that failed. The command's exit code is
+    /// included if the command failed because of it.
+    Error {
+        message: &'a str,
+        causes: Vec<String>,
+        code: Option<i32>,
+    },
+    /// A step of a command started, like building the library for a
+    /// platform.
+    StepStarted {
+        step: &'a str,
+        platform: Option<&'a str>,
+    },
+    /// A command, like cargo, cross, or docker, is about to be run. This is
+    /// only reported at the verbose level.
+    CommandRun {
+        command: &'a str,
+        dir: Option<&'a Path>,
+    },
+    /// A dynamic library was installed in the Godot project.
+    ArtifactInstalled { platform: &'a str, path: &'a Path },
+    /// A library was created.
+    LibraryCreated { name: &'a str, path: &'a Path },
+    /// A module was created.
+    ModuleCreated { name: &'a str, path: &'a Path },
+    /// A build for one of several platforms finished.
+    BuildFinished {
+        platform: &'a str,
+        profile: &'a str,
+        duration_secs: f64,
+        artifact_size: Option<u64>,
+        succeeded: bool,
+    },
+}
+
+/// Sets the format that events are reported in.
+///
+/// # Arguments
+///
+/// `message_format` - The format to report events in.
+pub fn set_message_format(message_format: MessageFormat) {
+    IS_JSON_MESSAGE_FORMAT.store(message_format == MessageFormat::Json, Ordering::Relaxed);
+}
+
+/// Returns the format that events are reported in.
+pub fn get_message_format() -> MessageFormat {
+    if IS_JSON_MESSAGE_FORMAT.load(Ordering::Relaxed) {
+        MessageFormat::Json
+    } else {
+        MessageFormat::Human
+    }
+}
+
+/// Sets how much is logged to the console.
+///
+/// # Arguments
+///
+/// `verbosity` - How much to log to the console.
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Returns how much is logged to the console.
+pub fn get_verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        2 => Verbosity::Verbose,
+        _ => Verbosity::VeryVerbose,
+    }
+}
+
+/// Turns off coloured messages on stdout or stderr when it isn't a terminal,
+/// like when it's redirected to a file, or on both when the `NO_COLOR`
+/// environment variable is set.
+pub fn set_color_from_env() {
+    let is_no_color_set = var_os("NO_COLOR").is_some_and(|no_color| !no_color.is_empty());
+    let is_stdout_colored = !is_no_color_set && stdout().is_terminal();
+    let is_stderr_colored = !is_no_color_set && stderr().is_terminal();
+    IS_STDOUT_COLORED.store(is_stdout_colored, Ordering::Relaxed);
+    IS_STDERR_COLORED.store(is_stderr_colored, Ordering::Relaxed);
+
+    // The messages are coloured if either one is and the colours are removed
+    // again from the messages written to the one that isn't.
+    colored::control::set_override(is_stdout_colored || is_stderr_colored);
+}
+
+/// Returns text to write to stdout, without its colours if stdout isn't
+/// coloured.
+///
+/// # Arguments
+///
+/// `text` - The text to write.
+fn for_stdout(text: &str) -> Cow<'_, str> {
+    if IS_STDOUT_COLORED.load(Ordering::Relaxed) {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(remove_colors(text))
+    }
+}
+
+/// Returns text to write to stderr, without its colours if stderr isn't
+/// coloured.
+///
+/// # Arguments
+///
+/// `text` - The text to write.
+fn for_stderr(text: &str) -> Cow<'_, str> {
+    if IS_STDERR_COLORED.load(Ordering::Relaxed) {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(remove_colors(text))
+    }
+}
+
+/// Removes the ANSI escape codes that colour text.
+///
+/// # Arguments
+///
+/// `text` - The text to remove the colours from.
+fn remove_colors(text: &str) -> String {
+    let mut uncolored_text = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            // An escape code ends with its first character in `@` to `~`,
+            // which is `m` for the ones that set colours.
+            for c in chars.by_ref().skip(1) {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        } else {
+            uncolored_text.push(c);
+        }
+    }
+
+    uncolored_text
+}
+
+/// Reports an event. Events are only written out with the json message
+/// format since the human message format logs messages instead.
+///
+/// # Arguments
+///
+/// `event` - The event to report.
+pub fn report(event: Event) {
+    if get_message_format() == MessageFormat::Json {
+        let event_json = serde_json::to_string(&event).expect("Unable to convert event to json");
+        let mut stdout = stdout().lock();
+        let _ = writeln!(stdout, "{}", event_json);
+        let _ = stdout.flush();
+    }
+}
+
+/// Writes a line meant for people to stdout, or to stderr with the json
+/// message format so that stdout only has events. Nothing is written in
+/// quiet mode.
+///
+/// # Arguments
+///
+/// `line` - The line to write.
+fn write_human_line(line: &str) {
+    if get_verbosity() == Verbosity::Quiet {
+        return;
+    }
+
+    if get_message_format() == MessageFormat::Json {
+        eprintln!("{}", for_stderr(line));
+    } else {
+        println!("{}", for_stdout(line));
+    }
+}
+
+/// Returns where child processes, like docker, should write their output so
+/// that it doesn't get mixed in with the events with the json message format.
+pub fn get_child_stdout() -> Stdio {
+    if get_message_format() == MessageFormat::Json {
+        Stdio::from(stderr())
+    } else {
+        Stdio::inherit()
+    }
+}
 
 /// Logs an error message to the console.
 ///
@@ -6,7 +288,52 @@ use colored::Colorize;
 ///
 /// `message` - The message to log to the console.
 pub fn log_error_to_console(message: &str) {
-    println!("Error: {}", message.red());
+    eprintln!("{}", for_stderr(&format!("Error: {}", message.red())));
+    report(Event::Error {
+        message,
+        causes: vec![],
+        code: None,
+    });
+}
+
+/// Logs an error that a command failed with, along with the chain of errors
+/// that caused it and the exit code that the cli exits with for it.
+///
+/// # Arguments
+///
+/// `error` - The error that the command failed with.
+pub fn log_command_error_to_console(error: &Error) {
+    let message = error.to_string();
+    eprintln!("{}", for_stderr(&format!("Error: {}", message.red())));
+
+    // Log the chain of errors that caused the error so that the user can see
+    // what went wrong at the lowest level.
+    let mut causes: Vec<String> = vec![];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        eprintln!(
+            "{}",
+            for_stderr(&format!("Error: {}", format!("Caused by: {}", cause).red()))
+        );
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+
+    report(Event::Error {
+        message: &message,
+        causes,
+        code: Some(error.exit_code()),
+    });
+}
+
+/// Logs a warning message to the console.
+///
+/// # Arguments
+///
+/// `message` - The message to log to the console.
+pub fn log_warning_to_console(message: &str) {
+    eprintln!("{}", for_stderr(&format!("Warning: {}", message.yellow())));
+    report(Event::Warning { message });
 }
 
 /// Logs an info message to the console.
@@ -15,7 +342,8 @@ pub fn log_error_to_console(message: &str) {
 ///
 /// `message` - The message to log to the console.
 pub fn log_info_to_console(message: &str) {
-    println!("Info: {}", message.cyan());
+    write_human_line(&format!("Info: {}", message.cyan()));
+    report(Event::Info { message });
 }
 
 /// Logs a success message to the console.
@@ -24,5 +352,147 @@ pub fn log_info_to_console(message: &str) {
 ///
 /// `message` - The message to log to the console.
 pub fn log_success_to_console(message: &str) {
-    println!("Success: {}", message.green());
+    write_human_line(&format!("Success: {}", message.green()));
+    report(Event::Success { message });
+}
+
+/// Logs a command that's about to be run, along with the directory that it's
+/// run in and the environment variables that it sets at the very verbose
+/// level. Nothing is logged unless the verbosity is at least verbose.
+///
+/// # Arguments
+///
+/// `command` - The command that's about to be run.
+pub fn log_command_to_console(command: &Command) {
+    let verbosity = get_verbosity();
+    if verbosity < Verbosity::Verbose {
+        return;
+    }
+
+    let command_line = std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| {
+            let part = part.to_string_lossy();
+            if part.is_empty() || part.contains(char::is_whitespace) {
+                format!("\"{}\"", part)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    let dir = command
+        .get_current_dir()
+        .filter(|_| verbosity == Verbosity::VeryVerbose);
+
+    match dir {
+        Some(dir) => write_human_line(&format!(
+            "Running: {} (in {})",
+            command_line.dimmed(),
+            dir.display()
+        )),
+        None => write_human_line(&format!("Running: {}", command_line.dimmed())),
+    }
+    if verbosity == Verbosity::VeryVerbose {
+        for (key, value) in command.get_envs() {
+            if let Some(value) = value {
+                let key = key.to_string_lossy();
+                // The logs can end up in CI logs so values that look like
+                // they're secret aren't logged.
+                let value = if is_secret_env_key(&key) {
+                    "<redacted>".to_string()
+                } else {
+                    value.to_string_lossy().to_string()
+                };
+                write_human_line(&format!("  {}={}", key, value));
+            }
+        }
+    }
+    report(Event::CommandRun {
+        command: &command_line,
+        dir,
+    });
+}
+
+/// Indicates whether the value of an environment variable could be a secret,
+/// like a token or a password, based on its name.
+///
+/// # Arguments
+///
+/// `key` - The name of the environment variable.
+fn is_secret_env_key(key: &str) -> bool {
+    const SECRET_KEY_PARTS: &[&str] = &[
+        "TOKEN",
+        "SECRET",
+        "PASSWORD",
+        "PASSWD",
+        "CREDENTIAL",
+        "AUTH",
+        "KEY",
+        "PRIVATE",
+    ];
+
+    let key = key.to_uppercase();
+    SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+/// Logs rows of text as a table with the columns lined up. The first row is
+/// the header of the table.
+///
+/// # Arguments
+///
+/// `rows` - The rows of the table.
+pub fn log_table_to_console(rows: &[Vec<String>]) {
+    let mut column_widths: Vec<usize> = vec![];
+    for row in rows {
+        for (column, cell) in row.iter().enumerate() {
+            match column_widths.get_mut(column) {
+                Some(width) => *width = (*width).max(cell.chars().count()),
+                None => column_widths.push(cell.chars().count()),
+            }
+        }
+    }
+
+    for (index, row) in rows.iter().enumerate() {
+        let line = row
+            .iter()
+            .zip(&column_widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ");
+        if index == 0 {
+            write_human_line(&line.trim_end().bold().to_string());
+        } else {
+            write_human_line(line.trim_end());
+        }
+    }
+}
+
+/// Asks the user a yes or no question in the console and returns whether
+/// they answered yes. Anything other than `y` or `yes`, including no answer
+/// at all, is treated as no.
+///
+/// # Arguments
+///
+/// `question` - The question to ask the user.
+pub fn ask_yes_no_in_console(question: &str) -> bool {
+    let prompt = format!("Question: {} [y/N] ", question.yellow());
+    if get_message_format() == MessageFormat::Json {
+        eprint!("{}", for_stderr(&prompt));
+        stderr()
+            .flush()
+            .expect("Unable to write question to the console");
+    } else {
+        print!("{}", for_stdout(&prompt));
+        stdout()
+            .flush()
+            .expect("Unable to write question to the console");
+    }
+
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
 }
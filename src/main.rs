@@ -1,30 +1,46 @@
-#[macro_use]
-
-mod commands;
-mod build_utils;
-mod cargo_config;
-mod definitions;
-mod gdnlib;
-mod gdns_file;
-mod log_utils;
-mod plugin_config;
-
-#[path = "./utils/config.rs"]
-mod config_utils;
-#[path = "./utils/cross.rs"]
-mod cross_utils;
-#[path = "./utils/file.rs"]
-mod file_utils;
-#[path = "./utils/lib.rs"]
-mod lib_utils;
-#[path = "./utils/path.rs"]
-mod path_utils;
-
+use std::env::current_dir;
 use std::path::PathBuf;
+use std::process::exit;
+use std::time::Duration;
 use structopt::StructOpt;
 
+use godot_rust_cli::log_utils::{
+    ask_yes_no_in_console, log_command_error_to_console, log_info_to_console, set_color_from_env,
+    set_message_format, set_verbosity, MessageFormat, Verbosity,
+};
+use godot_rust_cli::{
+    Api, BuildOptions, Context, Error, ModuleOptions, NewOptions, PlatformBackend, Project, Result,
+};
+
 #[derive(Debug, StructOpt)]
 #[structopt(about = "Provides an easy way to incorporate Rust components into your Godot project")]
+struct Cli {
+    /// The format to report the progress of the command in. With `json` one
+    /// json object is written to stdout for every event and the messages
+    /// that are usually written to stdout are written to stderr instead.
+    #[structopt(
+        long,
+        global = true,
+        default_value = "human",
+        possible_values = &["human", "json"]
+    )]
+    message_format: MessageFormat,
+
+    /// Logs the commands that are run, like cargo, cross, and docker. Pass
+    /// it twice to also log where they're run and to have cargo log
+    /// verbosely.
+    #[structopt(short, long, global = true, parse(from_occurrences))]
+    verbose: u64,
+
+    /// Only logs warnings and errors.
+    #[structopt(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    #[structopt(subcommand)]
+    command: GodotRustCli,
+}
+
+#[derive(Debug, StructOpt)]
 enum GodotRustCli {
     /// Creates a library for managing the Rust modules and creates the
     /// required gdnative files and directory structure within the Godot
@@ -45,6 +61,14 @@ enum GodotRustCli {
     /// mostly used for tests as skipping the build can cause Godot to throw
     /// errors about missing dynamic libraries for the project.
     ///
+    /// The --api option can be used to choose the Godot API that the library
+    /// is for. By default libraries are created for GDNative (Godot 3) but
+    /// passing `gdextension` will create a library for Godot 4 instead.
+    ///
+    /// The --workspace option can be used to create the library as a member
+    /// of an existing cargo workspace. The library is created in the
+    /// workspace's directory and added to its members.
+    ///
     /// # Examples
     ///
     /// ```
@@ -52,6 +76,12 @@ enum GodotRustCli {
     /// // in the same directory named platformer.
     /// godot-rust-cli new platformer-modules ./platformer
     /// ```
+    ///
+    /// ```
+    /// // Creating a new library named gameplay as a member of the workspace
+    /// // in the current directory.
+    /// godot-rust-cli new gameplay ./platformer --workspace .
+    /// ```
     New {
         /// The name of the library that will contain the Rust components. The
         /// library created is itself a cargo package so it needs to adhere to
@@ -73,6 +103,16 @@ enum GodotRustCli {
         /// that there's no missing dynamic library error in Godot.
         #[structopt(long, short)]
         skip_build: bool,
+
+        /// The Godot API that the library is for. This can be either
+        /// `gdnative` for Godot 3 or `gdextension` for Godot 4.
+        #[structopt(long, default_value = "gdnative", possible_values = &["gdnative", "gdextension"])]
+        api: Api,
+
+        /// The relative path to the directory of an existing cargo workspace
+        /// to create the library in as a member.
+        #[structopt(long, parse(from_os_str))]
+        workspace: Option<PathBuf>,
     },
 
     /// Creates a new rust module within the library's file system, adds its
@@ -81,18 +121,53 @@ enum GodotRustCli {
     ///
     /// The name of the module should be PascalCase.
     ///
+    /// The --inherits option can be used to choose the Godot class that the
+    /// module inherits from. By default modules inherit from Node2D, or from
+    /// EditorPlugin if the library is for a plugin.
+    ///
+    /// The --template option can be used to create the module from a template
+    /// in the library's `templates` directory or in the global
+    /// `$XDG_CONFIG_HOME/godot-rust-cli/templates` directory. If a template
+    /// named `default` exists it's used when no template is chosen.
+    ///
+    /// The --scene option can be used to create a scene with the module as
+    /// the script of its root node. The scene is created next to the gdns file
+    /// unless a path relative to the Godot project is passed.
+    ///
     /// # Examples
     ///
     /// ```
     /// // Creates a new module named Player
     /// godot-rust-cli create Player
     /// ```
+    ///
+    /// ```
+    /// // Creates a new module named Player that inherits from KinematicBody2D
+    /// godot-rust-cli create Player --inherits KinematicBody2D
+    /// ```
     Create {
         /// The name of the module to create. The component name should be
         /// PascalCase with examples including 'Player', 'Princess', 'Mob',
         /// etc.
         #[structopt()]
         name: String,
+
+        /// The Godot class that the module inherits from. The list of
+        /// supported classes can be found in the documentation on the create
+        /// command.
+        #[structopt(long)]
+        inherits: Option<String>,
+
+        /// The name of the template to create the module from, without the
+        /// `.rs` extension.
+        #[structopt(long)]
+        template: Option<String>,
+
+        /// Indicates whether a scene should be created for the module or not.
+        /// A path to the scene file, or to the directory to put it in, can be
+        /// passed relative to the Godot project.
+        #[structopt(long)]
+        scene: Option<Option<PathBuf>>,
     },
 
     /// Deletes a Rust module from the library's file system, removes its entry
@@ -101,6 +176,9 @@ enum GodotRustCli {
     /// The name passed to this command should be the same name that was used
     /// when the module was created.
     ///
+    /// If a scene was created for the module, godot-rust-cli will ask whether
+    /// it should be removed as well.
+    ///
     /// # Examples
     ///
     /// ```
@@ -115,19 +193,71 @@ enum GodotRustCli {
         name: String,
     },
 
+    /// Renames a Rust module in the library and the Godot project. This
+    /// renames the module's file, struct, and impl, its entries in the lib.rs
+    /// file, its gdns file, and updates every scene and resource in the Godot
+    /// project that references it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Renames a module named Player to Hero.
+    /// godot-rust-cli rename Player Hero
+    /// ```
+    Rename {
+        /// The current name of the module. This should be the same name that
+        /// was used when the module was created.
+        #[structopt()]
+        old_name: String,
+
+        /// The name to rename the module to.
+        #[structopt()]
+        new_name: String,
+    },
+
     /// Builds the dynamic library/libraries for the project and copies them to
     /// the Godot project.
     ///
     /// The --watch flag can be passed to have godot-rust-cli watch the src
-    /// directory for changes and rebuild automatically.
+    /// directory, manifest, and build script of the library and its local
+    /// path dependencies for changes and rebuild automatically. A build that
+    /// is still running when a newer change arrives is cancelled and started
+    /// again.
+    ///
+    /// The --delay option sets how many milliseconds to wait for changes to
+    /// settle before rebuilding while watching, which defaults to 500.
     ///
     /// The --release flag can be passed to have godot-rust-cli create a
     /// release build for the library instead of a debug build.
     ///
+    /// The --profile option can be passed to have godot-rust-cli build with
+    /// any cargo profile, like a custom one defined in the library's
+    /// Cargo.toml. The default profile and features can be set in the config
+    /// file.
+    ///
+    /// Any arguments after `--` are passed to cargo, or cross, as is.
+    ///
     /// The --build-all-platforms flag can be passed to have godot-rust-cli run a
     /// build for every platform in the config file. By default, a build will
     /// only be created for the native platform.
     ///
+    /// The --platform option can be passed to have godot-rust-cli also build
+    /// for a platform from the config file. It can be passed more than once.
+    ///
+    /// When building for more than one platform, the builds run at the same
+    /// time with their output written to log files under
+    /// `target/godot-rust-cli/` and a table summarizing them is shown at the
+    /// end. The --jobs option limits how many of them run at once.
+    ///
+    /// When watching, the native platform is rebuilt first and then the other
+    /// platforms are rebuilt while the watching continues.
+    ///
+    /// The --versioned flag can be passed to have godot-rust-cli copy the
+    /// dynamic library for the native platform to a new versioned file for
+    /// every build and point the gdnlib file to it. This lets the Godot editor
+    /// reload the library while watching without crashing or keeping the old
+    /// code. Old versioned copies are removed once they're no longer loaded.
+    ///
     /// # Examples
     ///
     /// ```
@@ -147,19 +277,55 @@ enum GodotRustCli {
     /// ```
     ///
     /// ```
+    /// // Building with a custom profile and a feature.
+    /// godot-rust-cli build --profile dev-opt -- --features hot-reload
+    /// ```
+    ///
+    /// ```
     /// // Building for all of the platforms in the config file.
     /// godot-rust-cli build --build-all-platforms
     /// ```
+    ///
+    /// ```
+    /// // Building for the native platform and windows.
+    /// godot-rust-cli build --platform windows
+    /// ```
+    ///
+    /// ```
+    /// // Watching for changes and rebuilding every platform in the config
+    /// // file.
+    /// godot-rust-cli build --watch --all
+    /// ```
+    ///
+    /// ```
+    /// // Watching for changes and waiting a second for them to settle.
+    /// godot-rust-cli build --watch --delay 1000
+    /// ```
+    ///
+    /// ```
+    /// // Watching for changes with the Godot editor open.
+    /// godot-rust-cli build --watch --versioned
+    /// ```
     Build {
         /// Indicates whether components should be watched for changes and be
         /// rebuild automatically or not.
         #[structopt(long, short)]
         watch: bool,
 
+        /// The number of milliseconds to wait for changes to settle before
+        /// rebuilding while watching.
+        #[structopt(long, default_value = "500")]
+        delay: u64,
+
         /// Indicates whether the build is a release build or not.
         #[structopt(long, short)]
         release: bool,
 
+        /// The cargo profile to build with, like a custom profile defined in
+        /// the library's Cargo.toml.
+        #[structopt(long, conflicts_with = "release")]
+        profile: Option<String>,
+
         /// Indicates whether godot-rust-cli should build for all of the
         /// platforms defined in the configuration or not.
         ///
@@ -167,6 +333,63 @@ enum GodotRustCli {
         /// user's native platform will be run.
         #[structopt(long, short)]
         all: bool,
+
+        /// A platform from the configuration to build for in addition to
+        /// the user's native platform. This can be passed more than once.
+        #[structopt(long = "platform", short = "p")]
+        platforms: Vec<String>,
+
+        /// The number of platforms that can be built at once when building
+        /// for more than one. By default every platform is built at once.
+        #[structopt(long, short)]
+        jobs: Option<usize>,
+
+        /// Indicates whether the dynamic library should be copied to a new
+        /// versioned file for every build so that the Godot editor can reload
+        /// it safely.
+        #[structopt(long)]
+        versioned: bool,
+
+        /// Extra arguments to pass to `cargo build` or `cross build`, passed
+        /// after `--`.
+        #[structopt(last = true)]
+        cargo_args: Vec<String>,
+    },
+
+    /// Adopts the Godot classes in the library that weren't created with the
+    /// create command so that they can be managed by godot-rust-cli.
+    ///
+    /// The library's src directory is scanned for classes that derive
+    /// NativeClass, or GodotClass for gdextension libraries, and each one that
+    /// isn't in the config is added to it along with the class it inherits
+    /// from. A gdns file is created in the Godot project for each adopted
+    /// class that doesn't already have one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Adopting the classes that were written by hand.
+    /// godot-rust-cli adopt
+    /// ```
+    #[structopt(alias = "scan")]
+    Adopt,
+
+    /// Checks that the library's config, lib.rs file, and the Godot project
+    /// still agree with each other and reports any problems found.
+    ///
+    /// The --fix flag can be passed to have godot-rust-cli fix the problems
+    /// that can be fixed automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Checking the library for problems and fixing what can be fixed.
+    /// godot-rust-cli doctor --fix
+    /// ```
+    Doctor {
+        /// Indicates whether the problems found should be fixed or not.
+        #[structopt(long, short)]
+        fix: bool,
     },
 
     /// Adds a platform to the list of platforms that the library can be built
@@ -182,11 +405,21 @@ enum GodotRustCli {
     /// // Adding Linux to the platforms that can be built for.
     /// godot-rust-cli add-platform "Linux"
     /// ```
+    ///
+    /// ```
+    /// // Adding Windows to be built with cargo-zigbuild instead of cross.
+    /// godot-rust-cli add-platform windows --backend zigbuild
+    /// ```
     AddPlatform {
         /// The name of the platform to add. The list of supported platforms
         /// can be found in the documentation on platforms.
         #[structopt()]
         name: String,
+
+        /// The backend to build the platform with instead of its default
+        /// one. This can be `cargo`, `cross`, `zigbuild`, or `local-linker`.
+        #[structopt(long, possible_values = &["cargo", "cross", "zigbuild", "local-linker"])]
+        backend: Option<PlatformBackend>,
     },
 
     /// Removes a platform from the list of platforms that the library can be
@@ -206,27 +439,133 @@ enum GodotRustCli {
 }
 
 fn main() {
-    match GodotRustCli::from_args() {
+    let cli = Cli::from_args();
+    set_message_format(cli.message_format);
+    set_verbosity(Verbosity::from_flags(cli.verbose, cli.quiet));
+    set_color_from_env();
+
+    if let Err(e) = run(cli.command) {
+        log_command_error_to_console(&e);
+        exit(e.exit_code());
+    }
+}
+
+/// Runs a command from the current directory, which is where libraries are
+/// created and where existing libraries are opened from.
+///
+/// # Arguments
+///
+/// `command` - The command to run.
+fn run(command: GodotRustCli) -> Result<()> {
+    let current_dir = current_dir().context("Unable to get current directory")?;
+
+    match command {
         GodotRustCli::New {
             name,
             godot_project_dir,
             plugin,
             skip_build,
-        } => commands::command_new(&name, godot_project_dir, plugin, skip_build),
-        GodotRustCli::Create { name } => commands::command_create(&name),
-        GodotRustCli::Destroy { name } => commands::command_destroy(&name),
+            api,
+            workspace,
+        } => {
+            let project = Project::new(
+                &current_dir,
+                &name,
+                &godot_project_dir,
+                NewOptions {
+                    plugin,
+                    api,
+                    workspace,
+                },
+            )?;
+
+            // For testing we skip building the library so that tests won't
+            // take a long time to run. Otherwise, in normal environments, we
+            // want to run the initial build or else Godot will throw errors
+            // stating it can't find the dynamic library for the project.
+            if skip_build {
+                Ok(())
+            } else {
+                project.build(BuildOptions::default())
+            }
+        }
+        GodotRustCli::Create {
+            name,
+            inherits,
+            template,
+            scene,
+        } => Project::open(&current_dir)?.create_module(
+            &name,
+            ModuleOptions {
+                inherits,
+                template,
+                scene,
+            },
+        ),
+        GodotRustCli::Destroy { name } => {
+            let mut project = Project::open(&current_dir)?;
+
+            // The scene could have been edited since it was created so we ask
+            // before removing it.
+            let remove_scene = project.module_scene_path(&name).is_some_and(|scene_path| {
+                ask_yes_no_in_console(&format!(
+                    "Remove the module's scene at {} as well?",
+                    scene_path.display()
+                ))
+            });
+
+            project.destroy_module(&name, remove_scene)
+        }
+        GodotRustCli::Rename { old_name, new_name } => {
+            Project::open(&current_dir)?.rename_module(&old_name, &new_name)
+        }
         GodotRustCli::Build {
             watch,
+            delay,
             release,
+            profile,
             all,
+            platforms,
+            jobs,
+            versioned,
+            cargo_args,
         } => {
-            if watch {
-                commands::command_build_and_watch(release);
-            } else {
-                commands::command_build(release, all);
+            let options = BuildOptions {
+                release,
+                profile,
+                cargo_args,
+                all_platforms: all,
+                platforms,
+                jobs,
+                watch,
+                watch_delay: Duration::from_millis(delay),
+                versioned,
+            };
+
+            // Running the build from the root of a workspace builds every
+            // library in it, which can't be watched all at once.
+            let projects = Project::open_all(&current_dir)?;
+            if projects.len() > 1 && options.watch {
+                return Err(Error::Invalid(
+                    "Only one library can be watched at a time, run the build from the library's directory to watch it".to_string(),
+                ));
+            }
+            for project in &projects {
+                if projects.len() > 1 {
+                    log_info_to_console(&format!("[build] building {}", project.config().name));
+                }
+                project.build(options.clone())?;
             }
+
+            Ok(())
+        }
+        GodotRustCli::Adopt => Project::open(&current_dir)?.adopt().map(|_| ()),
+        GodotRustCli::Doctor { fix } => Project::open(&current_dir)?.doctor(fix),
+        GodotRustCli::AddPlatform { name, backend } => {
+            Project::open(&current_dir)?.add_platform(&name, backend)
+        }
+        GodotRustCli::RemovePlatform { name } => {
+            Project::open(&current_dir)?.remove_platform(&name)
         }
-        GodotRustCli::AddPlatform { name } => commands::command_platform_add(&name),
-        GodotRustCli::RemovePlatform { name } => commands::command_platform_remove(&name),
     }
 }
@@ -0,0 +1,348 @@
+use std::collections::BTreeMap;
+
+use crate::config_utils::PlatformBackend;
+
+/// The tools that can build the library for a platform when it isn't the
+/// native platform. The native platform is always built with cargo. Every
+/// platform has a default backend which can be replaced by a backend chosen
+/// in the configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildBackend {
+    /// The library is built with `cargo build --target`, which needs the
+    /// target to be installed with rustup along with the platform's SDK.
+    Cargo,
+    /// The library is built with `cross build` in a docker container.
+    Cross,
+    /// The library is built with `cargo build --target` using the linker
+    /// for the target from the Android NDK on the host.
+    AndroidNdk {
+        /// The target that the NDK's clang wrappers are named after, which
+        /// isn't always the same as the Rust target.
+        clang_target: &'static str,
+    },
+    /// The library is built as an Emscripten side module with `cargo build
+    /// --target` if the Emscripten SDK is on the host, or with `cross build`
+    /// in the platform's docker image if it isn't.
+    Emscripten,
+    /// The library is built with `cargo zigbuild --target`, which links with
+    /// zig so that no linker or docker is needed for the target.
+    Zigbuild,
+    /// The library is built with `cargo build --target` using the linker
+    /// that's configured for the target in `.cargo/config.toml`.
+    LocalLinker,
+}
+
+impl From<PlatformBackend> for BuildBackend {
+    fn from(backend: PlatformBackend) -> Self {
+        match backend {
+            PlatformBackend::Cargo => BuildBackend::Cargo,
+            PlatformBackend::Cross => BuildBackend::Cross,
+            PlatformBackend::Zigbuild => BuildBackend::Zigbuild,
+            PlatformBackend::LocalLinker => BuildBackend::LocalLinker,
+        }
+    }
+}
+
+/// The docker image that cross uses to build the library for a platform
+/// instead of its default image.
+#[derive(Debug, Clone, Copy)]
+pub struct DockerImage {
+    /// The name of the docker file in the library's docker directory.
+    pub file_name: &'static str,
+    /// The contents of the docker file.
+    pub file_contents: &'static str,
+    /// The name:tag of the image that's built from the docker file.
+    pub tag: &'static str,
+}
+
+/// A platform that the library can be built for along with everything that
+/// the build and the Godot project need to know about it.
+#[derive(Debug, Clone, Copy)]
+pub struct Platform {
+    /// The name of the platform that's used in the configuration and by the
+    /// commands.
+    pub name: &'static str,
+    /// The feature tag of the platform's entry in the gdnlib file.
+    pub gdnative_feature_tag: &'static str,
+    /// The name of the platform in the feature tags of the gdextension file.
+    pub gdextension_os: &'static str,
+    /// The architecture in the feature tags of the gdextension file, if the
+    /// platform's feature tags have one.
+    pub gdextension_arch: Option<&'static str>,
+    /// The target triples that the library is built for. The libraries built
+    /// for platforms with more than one target are merged into a universal
+    /// binary.
+    pub rust_triples: &'static [&'static str],
+    /// The crate type that has to be built for the platform.
+    pub crate_type: &'static str,
+    /// The prefix of the library's file name.
+    pub library_prefix: &'static str,
+    /// The extension of the library's file name.
+    pub library_ext: &'static str,
+    /// The directory under `gdnative/bin` that the library is installed in.
+    pub bin_dir: &'static str,
+    /// The tool that builds the library for the platform by default.
+    pub build_backend: BuildBackend,
+    /// The docker image that cross uses for the platform, if it needs more
+    /// than the default one.
+    pub docker_image: Option<DockerImage>,
+}
+
+/// The platforms that the library can be built for. Platforms that share a
+/// feature tag, like `macos` and `macos-universal`, install their library in
+/// the same place.
+pub const PLATFORMS: &[Platform] = &[
+    Platform {
+        name: "linux",
+        gdnative_feature_tag: "X11.64",
+        gdextension_os: "linux",
+        gdextension_arch: Some("x86_64"),
+        rust_triples: &["x86_64-unknown-linux-gnu"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "so",
+        bin_dir: "linux",
+        build_backend: BuildBackend::Cross,
+        docker_image: Some(DockerImage {
+            file_name: "Dockerfile.x86_64-unknown-linux-gnu",
+            file_contents: include_str!("../docker/Dockerfile.x86_64-unknown-linux-gnu"),
+            tag: "godot-rust-cli-platform-linux:v1",
+        }),
+    },
+    Platform {
+        name: "windows",
+        gdnative_feature_tag: "Windows.64",
+        gdextension_os: "windows",
+        gdextension_arch: Some("x86_64"),
+        rust_triples: &["x86_64-pc-windows-gnu"],
+        crate_type: "cdylib",
+        library_prefix: "",
+        library_ext: "dll",
+        bin_dir: "windows",
+        build_backend: BuildBackend::Cross,
+        docker_image: Some(DockerImage {
+            file_name: "Dockerfile.x86_64-pc-windows-gnu",
+            file_contents: include_str!("../docker/Dockerfile.x86_64-pc-windows-gnu"),
+            tag: "godot-rust-cli-platform-windows:v1",
+        }),
+    },
+    Platform {
+        name: "macos",
+        gdnative_feature_tag: "OSX.64",
+        gdextension_os: "macos",
+        gdextension_arch: None,
+        rust_triples: &["x86_64-apple-darwin"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "dylib",
+        bin_dir: "macos",
+        build_backend: BuildBackend::Cargo,
+        docker_image: None,
+    },
+    Platform {
+        name: "macos-universal",
+        gdnative_feature_tag: "OSX.64",
+        gdextension_os: "macos",
+        gdextension_arch: None,
+        rust_triples: &["x86_64-apple-darwin", "aarch64-apple-darwin"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "dylib",
+        bin_dir: "macos",
+        build_backend: BuildBackend::Cargo,
+        docker_image: None,
+    },
+    Platform {
+        name: "android.arm64",
+        gdnative_feature_tag: "Android.arm64-v8a",
+        gdextension_os: "android",
+        gdextension_arch: Some("arm64"),
+        rust_triples: &["aarch64-linux-android"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "so",
+        bin_dir: "android/aarch64-linux-android",
+        build_backend: BuildBackend::AndroidNdk {
+            clang_target: "aarch64-linux-android",
+        },
+        docker_image: None,
+    },
+    Platform {
+        name: "android.armv7",
+        gdnative_feature_tag: "Android.armeabi-v7a",
+        gdextension_os: "android",
+        gdextension_arch: Some("arm32"),
+        rust_triples: &["armv7-linux-androideabi"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "so",
+        bin_dir: "android/armv7-linux-androideabi",
+        build_backend: BuildBackend::AndroidNdk {
+            clang_target: "armv7a-linux-androideabi",
+        },
+        docker_image: None,
+    },
+    Platform {
+        name: "android.x86_64",
+        gdnative_feature_tag: "Android.x86_64",
+        gdextension_os: "android",
+        gdextension_arch: Some("x86_64"),
+        rust_triples: &["x86_64-linux-android"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "so",
+        bin_dir: "android/x86_64-linux-android",
+        build_backend: BuildBackend::AndroidNdk {
+            clang_target: "x86_64-linux-android",
+        },
+        docker_image: None,
+    },
+    Platform {
+        name: "android.x86",
+        gdnative_feature_tag: "Android.x86",
+        gdextension_os: "android",
+        gdextension_arch: Some("x86_32"),
+        rust_triples: &["i686-linux-android"],
+        crate_type: "cdylib",
+        library_prefix: "lib",
+        library_ext: "so",
+        bin_dir: "android/i686-linux-android",
+        build_backend: BuildBackend::AndroidNdk {
+            clang_target: "i686-linux-android",
+        },
+        docker_image: None,
+    },
+    Platform {
+        name: "ios",
+        gdnative_feature_tag: "iOS.arm64",
+        gdextension_os: "ios",
+        gdextension_arch: None,
+        rust_triples: &["aarch64-apple-ios"],
+        crate_type: "staticlib",
+        library_prefix: "lib",
+        library_ext: "a",
+        bin_dir: "ios",
+        build_backend: BuildBackend::Cargo,
+        docker_image: None,
+    },
+    Platform {
+        name: "html5",
+        gdnative_feature_tag: "HTML5.wasm32",
+        gdextension_os: "web",
+        gdextension_arch: Some("wasm32"),
+        rust_triples: &["wasm32-unknown-emscripten"],
+        crate_type: "cdylib",
+        library_prefix: "",
+        library_ext: "wasm",
+        bin_dir: "html5",
+        build_backend: BuildBackend::Emscripten,
+        docker_image: Some(DockerImage {
+            file_name: "Dockerfile.wasm32-unknown-emscripten",
+            file_contents: include_str!("../docker/Dockerfile.wasm32-unknown-emscripten"),
+            tag: "godot-rust-cli-platform-html5:v1",
+        }),
+    },
+];
+
+impl Platform {
+    /// Returns the name of the library's file for the platform.
+    ///
+    /// # Arguments
+    ///
+    /// `rust_library_name_normalized` - The snake_case version of the Rust library name.
+    pub fn get_library_filename(&self, rust_library_name_normalized: &str) -> String {
+        format!(
+            "{}{}.{}",
+            self.library_prefix, rust_library_name_normalized, self.library_ext
+        )
+    }
+
+    /// Returns the backend that builds the library for the platform, which is
+    /// the one chosen for it in the configuration or its default one.
+    ///
+    /// # Arguments
+    ///
+    /// `platform_backends` - The backends chosen for platforms in the configuration.
+    pub fn get_build_backend(
+        &self,
+        platform_backends: &BTreeMap<String, PlatformBackend>,
+    ) -> BuildBackend {
+        platform_backends
+            .get(self.name)
+            .map(|backend| BuildBackend::from(*backend))
+            .unwrap_or(self.build_backend)
+    }
+
+    /// Returns the feature tag of the platform in the gdextension file for a
+    /// debug or release build.
+    ///
+    /// # Arguments
+    ///
+    /// `build` - Either `debug` or `release`.
+    pub fn get_gdextension_feature_tag(&self, build: &str) -> String {
+        match self.gdextension_arch {
+            Some(arch) => format!("{}.{}.{}", self.gdextension_os, build, arch),
+            None => format!("{}.{}", self.gdextension_os, build),
+        }
+    }
+}
+
+/// Other names that platforms can be referred to by along with the names of
+/// the platforms that they stand for. `android` used to be the only Android
+/// platform and `android.arm` was renamed after the ABI it builds for.
+pub const PLATFORM_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "android",
+        &[
+            "android.arm64",
+            "android.armv7",
+            "android.x86_64",
+            "android.x86",
+        ],
+    ),
+    ("android.arm", &["android.arm64"]),
+];
+
+/// Returns the platform with a name, if there is one. An alias is only
+/// resolved if it stands for a single platform.
+///
+/// # Arguments
+///
+/// `name` - The name of the platform, which isn't case sensitive.
+pub fn get_platform(name: &str) -> Option<&'static Platform> {
+    match get_platforms(name).as_slice() {
+        [platform] => Some(*platform),
+        _ => None,
+    }
+}
+
+/// Returns the platforms with a name, which is every platform that an alias
+/// stands for or the platform with the name if it isn't an alias.
+///
+/// # Arguments
+///
+/// `name` - The name of the platform or alias, which isn't case sensitive.
+pub fn get_platforms(name: &str) -> Vec<&'static Platform> {
+    let names = PLATFORM_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map_or_else(|| vec![name], |(_, names)| names.to_vec());
+
+    names
+        .iter()
+        .filter_map(|name| {
+            PLATFORMS
+                .iter()
+                .find(|platform| platform.name.eq_ignore_ascii_case(name))
+        })
+        .collect()
+}
+
+/// Returns the names of every platform as a comma separated list.
+pub fn get_platform_names() -> String {
+    PLATFORMS
+        .iter()
+        .map(|platform| platform.name)
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
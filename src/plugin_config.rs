@@ -1,7 +1,9 @@
-use std::{fs::read_to_string, path::PathBuf};
+use std::{fmt, fs::read_to_string, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Context, Result};
+
 /// The structure of a Godot project's plugin.cfg if the project is a plugin.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PluginConfig {
@@ -39,27 +41,20 @@ impl PluginConfig {
         }
     }
 
-    /// Returns the PluginConfig as a pretty printed toml string.
-    pub fn to_string(&mut self) -> String {
-        toml::to_string_pretty(self).expect("Unable to convert plugin config to string")
-    }
-
     /// Reads and returns the parsed contents of the `plugin.cfg` file.
     ///
     /// # Arguments
     ///
     /// `path` - The path to the `plugin.cfg` file.
     #[allow(dead_code)]
-    pub fn read(path: PathBuf) -> PluginConfig {
+    pub fn read(path: PathBuf) -> Result<PluginConfig> {
         // Read the contents of the plugin config file to a string.
         let plugin_config_toml_string =
-            read_to_string(path).expect("Unable to read the Godot project's plugin.cfg file");
+            read_to_string(path).context("Unable to read the Godot project's plugin.cfg file")?;
 
         // Next, we deserialize it into our structure.
-        let plugin_config_toml: PluginConfig = toml::from_str(&plugin_config_toml_string)
-            .expect("Unable to parse the Godot project's plugin.cfg file");
-
-        plugin_config_toml
+        toml::from_str(&plugin_config_toml_string)
+            .context("Unable to parse the Godot project's plugin.cfg file")
     }
 
     /// Writes the provided PluginConfig to the `plugin.cfg` file.
@@ -67,8 +62,18 @@ impl PluginConfig {
     /// # Arguments
     ///
     /// `path` - The path to write the `plugin.cfg` file to.
-    pub fn write(&mut self, path: PathBuf) {
+    pub fn write(&self, path: PathBuf) -> Result<()> {
         std::fs::write(path, self.to_string())
-            .expect("Unable to update contents of the Godot project's plugin.cfg file");
+            .context("Unable to update contents of the Godot project's plugin.cfg file")
+    }
+}
+
+impl fmt::Display for PluginConfig {
+    /// Writes the PluginConfig as a pretty printed toml string.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plugin_config_string =
+            toml::to_string_pretty(self).expect("Unable to convert plugin config to string");
+
+        write!(f, "{}", plugin_config_string)
     }
 }
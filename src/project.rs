@@ -0,0 +1,1642 @@
+use std::env::consts;
+use std::fs::{create_dir_all, read_to_string, remove_file, rename, write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use convert_case::{Case, Casing};
+use rust_codegen::Scope;
+use walkdir::WalkDir;
+
+use crate::build_utils::{
+    build_and_watch_for_changes, build_for_platform, build_for_platforms, BuildSettings,
+};
+use crate::cargo_config::{find_target_linker, CargoConfig};
+use crate::config_utils::{
+    add_module_to_config, add_platform_to_config, create_initial_config, get_config_as_object,
+    get_gdnlib_path, get_module_file_path, get_path_to_config_file, is_module_in_config,
+    is_platform_in_config, remove_module_from_config_if_exists,
+    remove_platform_from_config_if_exists, rename_module_in_config, Api, Config, PlatformBackend,
+};
+use crate::cross_utils::{add_image_override_for_platform, remove_docker_image_if_exists};
+use crate::definitions::{add_godot_dep, CargoToml};
+use crate::doctor_utils::find_problems;
+use crate::emscripten_utils::find_emcc;
+use crate::error::{Context, Error, Result};
+use crate::file_utils::write_and_fmt;
+use crate::gdextension::GdExtension;
+use crate::gdnlib::Gdnlib;
+use crate::gdns_file::GdnsFile;
+use crate::godot_classes::{GodotClass, GodotClassKind};
+use crate::lib_utils::{
+    add_module_to_lib, remove_module_from_lib, rename_idents_in_file, rename_module_in_lib,
+};
+use crate::log_utils::{
+    log_command_to_console, log_error_to_console, log_info_to_console, log_success_to_console,
+    log_warning_to_console, report, Event,
+};
+use crate::path_utils::{get_absolute_path, get_godot_res_path};
+use crate::platforms::{get_platform, get_platform_names, get_platforms, BuildBackend, Platform};
+use crate::plugin_config::PluginConfig;
+use crate::scan_utils::scan_library_classes;
+use crate::template_utils::{get_template, render_template, TemplateValues};
+use crate::tscn_file::TscnFile;
+use crate::workspace_utils::{
+    add_member_to_workspace, get_workspace_member_dirs, is_workspace_root,
+};
+
+/// A library used to manage Rust modules and the Godot project that it's
+/// for. Every path used by a project is relative to the library's directory
+/// so projects don't depend on the current directory.
+#[derive(Debug)]
+pub struct Project {
+    /// The path to the library's directory.
+    dir: PathBuf,
+    /// The configuration of the library.
+    config: Config,
+    /// The cargo configuration of the library which holds the path to the
+    /// Godot project.
+    cargo_config: CargoConfig,
+}
+
+/// The options used to create a library with [`Project::new`].
+#[derive(Debug, Clone, Default)]
+pub struct NewOptions {
+    /// Indicates whether the library is for a plugin or not.
+    pub plugin: bool,
+    /// The Godot API that the library is for.
+    pub api: Api,
+    /// The path to the cargo workspace to create the library in as a member,
+    /// relative to the directory passed to [`Project::new`] if it isn't
+    /// absolute.
+    pub workspace: Option<PathBuf>,
+}
+
+/// The options used to create a module with [`Project::create_module`].
+#[derive(Debug, Clone, Default)]
+pub struct ModuleOptions {
+    /// The Godot class that the module inherits from, if not the default one.
+    pub inherits: Option<String>,
+    /// The name of the template to create the module from, if any.
+    pub template: Option<String>,
+    /// Indicates whether a scene should be created for the module and the
+    /// path to create it at, if not next to the gdns file.
+    pub scene: Option<Option<PathBuf>>,
+}
+
+/// The options used to build a library with [`Project::build`].
+#[derive(Debug, Clone)]
+pub struct BuildOptions {
+    /// Indicates whether the build is a release build or not.
+    pub release: bool,
+    /// The cargo profile to build with, which can't be combined with
+    /// `release`. If neither is set, the profile in the configuration is
+    /// used or the `dev` profile if it doesn't have one.
+    pub profile: Option<String>,
+    /// The extra arguments to pass to `cargo build` or `cross build` after
+    /// the ones in the configuration.
+    pub cargo_args: Vec<String>,
+    /// Indicates whether all platforms should be built or just the native one.
+    pub all_platforms: bool,
+    /// The platforms from the configuration to build for in addition to the
+    /// native one, when not building for all of them.
+    pub platforms: Vec<String>,
+    /// The number of platforms that can be built at once when building for
+    /// more than one. Every platform is built at once if this isn't set.
+    pub jobs: Option<usize>,
+    /// Indicates whether the library should be rebuilt whenever its `src`
+    /// directory, its manifest, its build script, or those of its local path
+    /// dependencies change. The native platform is rebuilt first and then
+    /// any other platforms are rebuilt while the watching continues. This
+    /// doesn't return unless an error happens.
+    pub watch: bool,
+    /// How long to wait for changes to settle before rebuilding while
+    /// watching.
+    pub watch_delay: Duration,
+    /// Indicates whether the native platform's dynamic library should be
+    /// copied to a new versioned file for every build, with the gdnlib file
+    /// pointed at it, so that the Godot editor can reload it safely. Only
+    /// GDNative libraries can be versioned.
+    pub versioned: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> BuildOptions {
+        BuildOptions {
+            release: false,
+            profile: None,
+            cargo_args: vec![],
+            all_platforms: false,
+            platforms: vec![],
+            jobs: None,
+            watch: false,
+            watch_delay: Duration::from_millis(500),
+            versioned: false,
+        }
+    }
+}
+
+impl Project {
+    /// Creates the library used to manage Rust modules and returns it.
+    ///
+    /// # Arguments
+    ///
+    /// `dir` - The path to the directory to create the library in.
+    /// `name` - The name of the library.
+    /// `godot_project_dir` - The path to the directory of the Godot project the plugin or modules are for, relative to `dir` if it isn't absolute.
+    /// `options` - The options to create the library with.
+    pub fn new(
+        dir: &Path,
+        name: &str,
+        godot_project_dir: &Path,
+        options: NewOptions,
+    ) -> Result<Project> {
+        log_info_to_console("Creating library");
+
+        // The input from the user could be in any format but as is standard
+        // with Rust, we want to make sure that the library has a snake_case
+        // name so we enforce that here in case it is not already.
+        let library_name_normalized = name.to_case(Case::Snake);
+
+        // Libraries that are members of a workspace are created in the
+        // workspace's directory instead.
+        let workspace_absolute_path = options
+            .workspace
+            .as_ref()
+            .map(|workspace_dir| {
+                get_absolute_path(dir, workspace_dir).context(format!(
+                    "Unable to find the workspace at {}",
+                    workspace_dir.display()
+                ))
+            })
+            .transpose()?;
+        if let Some(workspace_absolute_path) = &workspace_absolute_path {
+            if !is_workspace_root(workspace_absolute_path) {
+                return Err(Error::Invalid(format!(
+                    "{} isn't a cargo workspace because its Cargo.toml doesn't have a [workspace] table",
+                    workspace_absolute_path.display()
+                )));
+            }
+        }
+        let library_parent_dir = workspace_absolute_path.as_deref().unwrap_or(dir);
+
+        // Create an absolute path from the directory to create the library in
+        // and then the normalized version of the Rust library name.
+        let library_absolute_path =
+            get_absolute_path(library_parent_dir, Path::new(&library_name_normalized))
+                .context("Unable to create the path to the library")?;
+
+        // To make it easier to write to the Godot project we also want to
+        // create an absolute path to it. A Godot project whose directory
+        // can't be found isn't valid.
+        let godot_project_absolute_path = get_absolute_path(dir, godot_project_dir)
+            .map_err(|_| Error::GodotProjectInvalid(dir.join(godot_project_dir)))?;
+
+        // If there's already a directory with the library name then we return
+        // early with an error.
+        if library_absolute_path.exists() {
+            return Err(Error::Invalid(
+                "Cannot create library, directory with the same name already exists".to_string(),
+            ));
+        }
+
+        // If there's not a project.godot file at the root of the provided
+        // Godot project directory then we return early with an error.
+        if !godot_project_absolute_path.join("project.godot").exists() {
+            return Err(Error::GodotProjectInvalid(godot_project_absolute_path));
+        }
+
+        // Godot 4 editor plugins are registered differently than Godot 3
+        // plugins so plugin libraries can only be created for GDNative for
+        // now.
+        if options.plugin && options.api == Api::Gdextension {
+            return Err(Error::Invalid(
+                "Plugin libraries can only be created for the gdnative api".to_string(),
+            ));
+        }
+
+        // Creates the library using the `cargo new --lib` command.
+        let message = "Unable to create the library with cargo new".to_string();
+        let mut cargo_new_command = Command::new("cargo");
+        cargo_new_command
+            .arg("new")
+            .arg(&library_name_normalized)
+            .arg("--lib")
+            .current_dir(library_parent_dir);
+        log_command_to_console(&cargo_new_command);
+        match cargo_new_command.output() {
+            Ok(output) if output.status.success() => (),
+            Ok(output) => {
+                return Err(Error::CargoFailed {
+                    message,
+                    status: Some(output.status),
+                    source: None,
+                })
+            }
+            Err(e) => {
+                return Err(Error::CargoFailed {
+                    message,
+                    status: None,
+                    source: Some(e),
+                })
+            }
+        }
+
+        // Newer versions of cargo add the library to the workspace's members
+        // themselves so it's only added if it isn't a member yet.
+        if let Some(workspace_absolute_path) = &workspace_absolute_path {
+            log_info_to_console("Adding the library to the workspace");
+            add_member_to_workspace(workspace_absolute_path, &library_absolute_path)?;
+        }
+
+        // Since we have custom configuration for the Godot project that needs
+        // to be used as env variables, we have to create the initial
+        // config.toml file similarly to how we replaced the Cargo.toml file.
+        let godot_project_absolute_path_as_string = godot_project_absolute_path
+            .to_str()
+            .ok_or_else(|| {
+                Error::Invalid(format!(
+                    "The path to the Godot project, {}, has to be valid unicode to be saved in the config",
+                    godot_project_absolute_path.display()
+                ))
+            })?
+            .to_string();
+        let cargo_config = CargoConfig::new(&godot_project_absolute_path_as_string);
+        cargo_config.write(&library_absolute_path)?;
+
+        // Get the base Cargo.toml contents of the library.
+        let library_cargo_toml_path = library_absolute_path.join("Cargo.toml");
+        let library_cargo_toml_string = read_to_string(&library_cargo_toml_path)
+            .context("Unable to read library's Cargo.toml file while creating the library")?;
+
+        // Add the necessary dependencies to the base contents.
+        let mut new_library_cargo_toml: CargoToml = toml::from_str(&library_cargo_toml_string)
+            .context("Unable to parse the library's Cargo.toml file")?;
+
+        // The gdnative dependency is added by default so if the library is for
+        // GDExtension we have to swap it out for the godot dependency.
+        if options.api == Api::Gdextension {
+            new_library_cargo_toml.dependencies.gdnative = None;
+            new_library_cargo_toml.dependencies.godot = add_godot_dep();
+        }
+
+        // Turn the new contents of the library's Cargo.toml into a string so
+        // that we can write it back to the library. We also need to normalize
+        // some things here because when we turn the Cargo toml contents to a
+        // string, extra symbols get added.
+        let new_library_cargo_toml_string = toml::to_string(&new_library_cargo_toml)
+            .context(
+                "Unable to convert the library's new Cargo.toml to a string while creating the library",
+            )?
+            .replace("\\", "")
+            .replace("\"{", "{")
+            .replace("}\"", "}");
+
+        // Next we overwrite the contents of the Cargo.toml file with our
+        // contents.
+        write(&library_cargo_toml_path, new_library_cargo_toml_string).context(
+            "Unable to update contents of the library's Cargo.toml file while creating the library",
+        )?;
+
+        let godot_project_dir_name = godot_project_absolute_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or_else(|| Error::GodotProjectInvalid(godot_project_absolute_path.clone()))?
+            .to_string();
+        let config = create_initial_config(
+            &library_absolute_path,
+            name.to_owned(),
+            godot_project_dir_name,
+            options.plugin,
+            options.api,
+        )?;
+
+        // Build the initial contents of the Rust library's `lib.rs` file which
+        // is used to initialize Godot. GDNative libraries register their
+        // classes in the `init` function while GDExtension libraries only need
+        // an entry point since classes are registered automatically.
+        log_info_to_console("Creating initial lib.rs file");
+        let mut scope = Scope::new();
+        match options.api {
+            Api::Gdnative => {
+                scope.import("gdnative::prelude", "*");
+                scope.new_fn("init").arg("handle", "InitHandle");
+                scope.raw("godot_init!(init);");
+            }
+            Api::Gdextension => {
+                let library_name_pascal_case = name.to_case(Case::Pascal);
+                scope.import("godot::prelude", "*");
+                scope.new_struct(&library_name_pascal_case);
+                scope.raw(&format!(
+                    "#[gdextension]\nunsafe impl ExtensionLibrary for {} {{}}",
+                    &library_name_pascal_case
+                ));
+            }
+        }
+        write(
+            library_absolute_path.join("src").join("lib.rs"),
+            scope.to_string(),
+        )
+        .context("Unable to create the initial lib.rs file")?;
+
+        let mut project = Project {
+            dir: library_absolute_path,
+            config,
+            cargo_config,
+        };
+
+        if options.plugin {
+            let module_name_snake_case = &name.to_case(Case::Snake);
+
+            let godot_plugin_dir = godot_project_absolute_path
+                .join("addons")
+                .join(module_name_snake_case);
+            let godot_plugin_cfg = godot_plugin_dir.join("plugin.cfg");
+            create_dir_all(&godot_plugin_dir)
+            .context("Unable to create the plugin directory structure in Godot project while creating the library")?;
+
+            project.create_module(name, ModuleOptions::default())?;
+
+            // Every Godot plugin needs to have a config file that describes
+            // the plugin.
+            // More about this can be found at: https://docs.godotengine.org/en/stable/tutorials/plugins/editor/making_plugins.html
+            let plugin = PluginConfig::new(name, &format!("{}.gdns", &library_name_normalized));
+            plugin.write(godot_plugin_cfg)?;
+        }
+
+        // Create the initial gdnlib file for the Godot project, or the
+        // gdextension file if the library is for Godot 4. This file points to
+        // the binaries for popular operating systems so that Godot knows which
+        // one to use.
+        let (gdnlib_pretty_printed, gdnlib_ext) = match options.api {
+            Api::Gdnative => (
+                Gdnlib::new(&library_name_normalized, options.plugin).to_string(),
+                "gdnlib",
+            ),
+            Api::Gdextension => (
+                GdExtension::new(&library_name_normalized).to_string(),
+                "gdextension",
+            ),
+        };
+
+        // Next, we create the directory to where the gndlib file will be saved
+        // in the Godot project. As with most operations in the Godot project
+        // we have to handle this differently if the Godot project is a plugin.
+        let gdnlib_dir: PathBuf = if options.plugin {
+            godot_project_absolute_path
+                .join("addons")
+                .join(&library_name_normalized)
+                .join("gdnative")
+        } else {
+            godot_project_absolute_path.join("gdnative")
+        };
+        create_dir_all(&gdnlib_dir).context("Unable to create directory for the gdnlib file")?;
+
+        // Using the directory defined above we can create the path to the
+        // gdnlib file which we will write in the next step.
+        let gdnlib_file_path =
+            gdnlib_dir.join(format!("{}.{}", &library_name_normalized, gdnlib_ext));
+
+        // Finally we can write the gndlib file to the Godot project.
+        log_info_to_console(&format!(
+            "Creating the {} file in the Godot project",
+            gdnlib_ext
+        ));
+        write(&gdnlib_file_path, gdnlib_pretty_printed)
+            .context(format!("Unable to create the {} file", gdnlib_ext))?;
+
+        log_success_to_console("library created");
+        report(Event::LibraryCreated {
+            name,
+            path: &project.dir,
+        });
+
+        Ok(project)
+    }
+
+    /// Opens an existing library. An error is returned if the directory
+    /// doesn't have a godot-rust-cli config file.
+    ///
+    /// # Arguments
+    ///
+    /// `dir` - The path to the library's directory.
+    pub fn open(dir: &Path) -> Result<Project> {
+        let config_file_path = get_path_to_config_file(dir);
+        if !config_file_path.exists() {
+            return Err(Error::ConfigMissing(config_file_path));
+        }
+
+        // Read the cargo config so that we can get the path to the Godot
+        // project from the env vars.
+        Ok(Project {
+            dir: dir.to_path_buf(),
+            config: get_config_as_object(dir)?,
+            cargo_config: CargoConfig::read(dir)?,
+        })
+    }
+
+    /// Opens the library in a directory or, if the directory is the root of
+    /// a cargo workspace without a library of its own, every member of the
+    /// workspace that is a library. Members that aren't libraries, like
+    /// crates of pure Rust, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// `dir` - The path to the library's or the workspace's directory.
+    pub fn open_all(dir: &Path) -> Result<Vec<Project>> {
+        let config_file_path = get_path_to_config_file(dir);
+        if config_file_path.exists() || !is_workspace_root(dir) {
+            return Ok(vec![Project::open(dir)?]);
+        }
+
+        let projects = get_workspace_member_dirs(dir)?
+            .iter()
+            .filter(|member_dir| get_path_to_config_file(member_dir).exists())
+            .map(|member_dir| Project::open(member_dir))
+            .collect::<Result<Vec<Project>>>()?;
+        if projects.is_empty() {
+            return Err(Error::ConfigMissing(config_file_path));
+        }
+
+        Ok(projects)
+    }
+
+    /// Returns the path to the library's directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the configuration of the library.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Returns the path to the Godot project that the library is for.
+    pub fn godot_project_path(&self) -> PathBuf {
+        PathBuf::from(&self.cargo_config.env.godot_project_path)
+    }
+
+    /// Returns the path to the scene that was created for a module, if the
+    /// module has one and it still exists in the Godot project.
+    ///
+    /// # Arguments
+    ///
+    /// `name` - The name of the module.
+    pub fn module_scene_path(&self, name: &str) -> Option<PathBuf> {
+        self.config
+            .module_scenes
+            .get(&name.to_case(Case::Pascal))
+            .map(|scene_path| self.godot_project_path().join(scene_path))
+            .filter(|scene_path| scene_path.exists())
+    }
+
+    /// Creates a module by creating a module for it inside the library and a
+    /// corresponding gdns file in the Godot project.
+    ///
+    /// # Arguments
+    ///
+    /// `name` - The name of the module to create as pascal case.
+    /// `options` - The options to create the module with.
+    pub fn create_module(&mut self, name: &str, options: ModuleOptions) -> Result<()> {
+        let module_name_snake_case = &name.to_case(Case::Snake);
+        let module_name_pascal_case = &name.to_case(Case::Pascal);
+
+        log_info_to_console("Creating module");
+
+        if is_module_in_config(module_name_pascal_case, &mut self.config) {
+            // If there's already a module with the same name in the config,
+            // then we exit early to avoid creating duplicates.
+            return Err(Error::Invalid(
+                "A module with the same name already exists".to_string(),
+            ));
+        }
+
+        // The class that the module inherits from is looked up in the table
+        // of known Godot classes so that the right lifecycle methods are
+        // generated.
+        let godot_class = match options.inherits.as_deref() {
+            Some(class_name) => match GodotClass::find(class_name, self.config.api) {
+                Some(godot_class) => godot_class,
+                None => {
+                    return Err(Error::Invalid(format!(
+                        "{} isn't a known Godot class, the known classes are: {}",
+                        class_name,
+                        GodotClass::names_for_api(self.config.api).join(", ")
+                    )))
+                }
+            },
+            None => GodotClass::default_for_library(self.config.is_plugin),
+        };
+
+        // Scenes need a node at their root so they can't be created for
+        // modules that aren't nodes.
+        let is_node_class = !matches!(
+            godot_class.kind,
+            GodotClassKind::Resource | GodotClassKind::EditorPlugin
+        );
+        if options.scene.is_some() && !is_node_class {
+            return Err(Error::Invalid(format!(
+                "A scene can't be created for a module that inherits from {}",
+                godot_class.name
+            )));
+        }
+
+        // If a template was chosen, or the user has a default template, the
+        // module is created from it. Otherwise we build the script based on
+        // the Godot API the library is for and whether the Godot project is a
+        // plugin or not.
+        let module_template = match options.template.as_deref() {
+            Some(template_name) => match get_template(&self.dir, template_name)? {
+                Some(module_template) => Some(module_template),
+                None => {
+                    return Err(Error::Invalid(format!(
+                        "The template {} doesn't exist in any of the templates directories",
+                        template_name
+                    )))
+                }
+            },
+            None => get_template(&self.dir, "default")?,
+        };
+
+        let module_code = match module_template {
+            Some(module_template) => render_template(
+                &module_template,
+                &TemplateValues {
+                    pascal: module_name_pascal_case,
+                    snake: module_name_snake_case,
+                    base: godot_class.name,
+                    library: &self.config.name.to_case(Case::Snake),
+                },
+            ),
+            None => match self.config.api {
+                Api::Gdnative => create_gdnative_module_scope(
+                    module_name_pascal_case,
+                    &godot_class,
+                    self.config.is_plugin,
+                )
+                .to_string(),
+                Api::Gdextension => {
+                    create_gdextension_module_scope(module_name_pascal_case, &godot_class)
+                        .to_string()
+                }
+            },
+        };
+
+        // Write the code out to a file in the library.
+        let module_file_path = self
+            .dir
+            .join("src")
+            .join(format!("{}.rs", &module_name_snake_case));
+        write_and_fmt(&module_file_path, module_code).context(
+            "Unable to create the initial script file in the library while creating a module",
+        )?;
+
+        add_module_to_lib(&self.dir, name, &self.config)?;
+
+        // Creates the gdns file for the module from the template and places it
+        // either in the gdnative directory at the root of the Godot project if
+        // it is a normal library or in the gdnative directory at the root of
+        // the plugin directory in the Godot project if it is a plugin library.
+        let gdns_file_name = format!("{}.gdns", &module_name_snake_case);
+        let gdns_dir = get_gdns_dir(&self.config, &self.cargo_config);
+
+        create_dir_all(&gdns_dir)
+            .context("Unable to create directory for module file in Godot.")?;
+
+        // GDExtension classes are registered with Godot automatically so
+        // there's no gdns file to create for them.
+        if self.config.api == Api::Gdnative {
+            // The path to the gdnlib file in the Godot project.
+            let gdnlib_path = get_gdnlib_path(&self.config);
+
+            // Create the gdns file which defines the script in the Godot
+            // project.
+            let gdns_file = GdnsFile::new(module_name_pascal_case, &gdnlib_path);
+            gdns_file.write(gdns_dir.join(&gdns_file_name))?;
+        }
+
+        if let Some(scene_path) = options.scene {
+            let godot_project_path = self.godot_project_path();
+
+            // The scene is placed next to the gdns file unless a path is
+            // given, which can either be the path to the scene file or to the
+            // directory to put it in.
+            let scene_file_name = format!("{}.tscn", &module_name_snake_case);
+            let scene_path = match scene_path {
+                Some(scene_path)
+                    if scene_path.extension().and_then(|ext| ext.to_str()) == Some("tscn") =>
+                {
+                    godot_project_path.join(scene_path)
+                }
+                Some(scene_dir) => godot_project_path.join(scene_dir).join(&scene_file_name),
+                None => gdns_dir.join(&scene_file_name),
+            };
+            if let Some(scene_dir) = scene_path.parent() {
+                create_dir_all(scene_dir)
+                    .context("Unable to create directory for the scene in Godot.")?;
+            }
+
+            // GDNative scenes use the module's gdns file as the script of a
+            // node of the inherited class while GDExtension classes are nodes
+            // themselves.
+            let tscn_file = match self.config.api {
+                Api::Gdnative => {
+                    let gdns_path = gdns_dir.join(&gdns_file_name);
+                    let gdns_res_path = get_godot_res_path(&godot_project_path, &gdns_path);
+                    TscnFile::new(
+                        module_name_pascal_case,
+                        godot_class.name,
+                        Some(&gdns_res_path),
+                    )
+                }
+                Api::Gdextension => {
+                    TscnFile::new(module_name_pascal_case, module_name_pascal_case, None)
+                }
+            };
+            tscn_file.write(scene_path.clone())?;
+
+            self.config.module_scenes.insert(
+                module_name_pascal_case.to_string(),
+                get_godot_res_path(&godot_project_path, &scene_path),
+            );
+        }
+
+        add_module_to_config(
+            &self.dir,
+            module_name_pascal_case,
+            Some(godot_class.name),
+            &mut self.config,
+        )?;
+
+        log_success_to_console("Module created");
+        report(Event::ModuleCreated {
+            name,
+            path: &module_file_path,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a module by deleting its module file from the library and
+    /// searching the Godot project for the corresponding gdns file to remove.
+    ///
+    /// # Arguments
+    ///
+    /// `name` - The name of the module to remove.
+    /// `remove_scene` - Indicates whether the scene created for the module should be removed as well.
+    pub fn destroy_module(&mut self, name: &str, remove_scene: bool) -> Result<()> {
+        log_info_to_console("destroying module...");
+
+        let module_name_snake_case = name.to_case(Case::Snake);
+        let module_name_pascal_case = name.to_case(Case::Pascal);
+
+        // The module's scene and file have to be looked up before the module
+        // is removed from the config.
+        let scene_path = self
+            .module_scene_path(&module_name_pascal_case)
+            .filter(|_| remove_scene);
+        let module_file_path = get_module_file_path(&module_name_pascal_case, &self.config);
+        let is_adopted = self
+            .config
+            .module_files
+            .contains_key(&module_name_pascal_case);
+
+        remove_module_from_config_if_exists(&self.dir, &module_name_pascal_case, &mut self.config)?;
+
+        if let Some(scene_path) = scene_path {
+            remove_file(&scene_path).context(
+                "Unable to remove the module's scene from the Godot project while destroying the module",
+            )?;
+        }
+
+        // GDExtension modules don't have a gdns file so there's only
+        // something to remove from the Godot project for GDNative modules.
+        if self.config.api == Api::Gdnative {
+            remove_gdns_file_from_godot_project(
+                &module_name_snake_case,
+                &self.config,
+                &self.cargo_config,
+            )?;
+        }
+
+        if is_adopted {
+            // Adopted modules can share their file with other code so their
+            // code is left for the user to remove.
+            log_info_to_console(&format!(
+                "The code for the module in {} was left in place since the module was adopted",
+                module_file_path.display()
+            ));
+        } else {
+            // Removes all traces of a module from the lib.rs file.
+            remove_module_from_lib(&self.dir, &module_name_pascal_case)?;
+
+            // Removes the module's file from the library.
+            remove_file(self.dir.join(module_file_path)).context(
+                "Unable to remove the module file from the library while destroying the module",
+            )?;
+        }
+
+        log_success_to_console("Module destroyed");
+
+        Ok(())
+    }
+
+    /// Renames a module in the library and the Godot project. The module's
+    /// file, its struct and impl, its entries in the lib file and config, and
+    /// its gdns file are renamed and every scene and resource in the Godot
+    /// project that references the gdns file is updated to point to the
+    /// renamed one.
+    ///
+    /// # Arguments
+    ///
+    /// `old_name` - The current name of the module.
+    /// `new_name` - The name to rename the module to.
+    pub fn rename_module(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        log_info_to_console("renaming module...");
+
+        let old_module_name_snake_case = old_name.to_case(Case::Snake);
+        let old_module_name_pascal_case = old_name.to_case(Case::Pascal);
+        let new_module_name_snake_case = new_name.to_case(Case::Snake);
+        let new_module_name_pascal_case = new_name.to_case(Case::Pascal);
+
+        let godot_project_path = self.godot_project_path();
+
+        if !is_module_in_config(&old_module_name_pascal_case, &mut self.config) {
+            return Err(Error::Invalid(
+                "The module to rename doesn't exist".to_string(),
+            ));
+        }
+
+        let new_module_file_path = self
+            .dir
+            .join("src")
+            .join(format!("{}.rs", &new_module_name_snake_case));
+        if is_module_in_config(&new_module_name_pascal_case, &mut self.config)
+            || new_module_file_path.exists()
+        {
+            return Err(Error::Invalid(
+                "A module with the new name already exists".to_string(),
+            ));
+        }
+
+        let class_renames = [(
+            old_module_name_pascal_case.to_owned(),
+            new_module_name_pascal_case.to_owned(),
+        )];
+
+        // Adopted modules can share their file with other code so only the
+        // struct and impl are renamed in them. Otherwise, the module's file
+        // is renamed along with the struct and impl in it.
+        match self.config.module_files.get(&old_module_name_pascal_case) {
+            Some(module_file_name) => {
+                rename_idents_in_file(&self.dir.join(module_file_name), &class_renames)?;
+                rename_idents_in_file(&self.dir.join("src").join("lib.rs"), &class_renames)?;
+            }
+            None => {
+                rename(
+                    self.dir
+                        .join("src")
+                        .join(format!("{}.rs", &old_module_name_snake_case)),
+                    &new_module_file_path,
+                )
+                .context(
+                    "Unable to rename the module file in the library while renaming the module",
+                )?;
+                rename_idents_in_file(&new_module_file_path, &class_renames)?;
+
+                rename_module_in_lib(
+                    &self.dir,
+                    &old_module_name_pascal_case,
+                    &new_module_name_pascal_case,
+                )?;
+            }
+        }
+
+        // The references to the module in the Godot project are either to its
+        // gdns file for GDNative modules or to the class itself for
+        // GDExtension modules.
+        let mut reference_renames: Vec<(String, String)> = vec![];
+        if self.config.api == Api::Gdnative {
+            for old_gdns_path in find_gdns_files_in_godot_project(
+                &old_module_name_snake_case,
+                &self.config,
+                &self.cargo_config,
+            ) {
+                let new_gdns_path =
+                    old_gdns_path.with_file_name(format!("{}.gdns", &new_module_name_snake_case));
+
+                let gdns_contents = read_to_string(&old_gdns_path)
+                    .context("Unable to read the module's gdns file while renaming the module")?
+                    .replace(
+                        &format!("resource_name = \"{}\"", &old_module_name_pascal_case),
+                        &format!("resource_name = \"{}\"", &new_module_name_pascal_case),
+                    )
+                    .replace(
+                        &format!("class_name = \"{}\"", &old_module_name_pascal_case),
+                        &format!("class_name = \"{}\"", &new_module_name_pascal_case),
+                    );
+                write(&new_gdns_path, gdns_contents)
+                    .context("Unable to write the module's gdns file while renaming the module")?;
+                remove_file(&old_gdns_path).context(
+                    "Unable to remove the module's old gdns file while renaming the module",
+                )?;
+
+                reference_renames.push((
+                    format!(
+                        "path=\"res://{}\"",
+                        get_godot_res_path(&godot_project_path, &old_gdns_path)
+                    ),
+                    format!(
+                        "path=\"res://{}\"",
+                        get_godot_res_path(&godot_project_path, &new_gdns_path)
+                    ),
+                ));
+            }
+        } else {
+            reference_renames.push((
+                format!("type=\"{}\"", &old_module_name_pascal_case),
+                format!("type=\"{}\"", &new_module_name_pascal_case),
+            ));
+        }
+
+        // Update every scene and resource in the Godot project that
+        // references the module.
+        for entry in WalkDir::new(&godot_project_path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let extension = entry.path().extension().and_then(|ext| ext.to_str());
+            if extension != Some("tscn") && extension != Some("tres") {
+                continue;
+            }
+
+            let contents = match read_to_string(entry.path()) {
+                Ok(contents) => contents,
+                Err(_) => continue,
+            };
+            let renamed_contents = reference_renames
+                .iter()
+                .fold(contents.clone(), |renamed_contents, (old, new)| {
+                    renamed_contents.replace(old, new)
+                });
+
+            if renamed_contents != contents {
+                write(entry.path(), renamed_contents)
+                    .context("Unable to update a reference to the module in the Godot project")?;
+            }
+        }
+
+        rename_module_in_config(
+            &self.dir,
+            &old_module_name_pascal_case,
+            &new_module_name_pascal_case,
+            &mut self.config,
+        )?;
+
+        log_success_to_console("Module renamed");
+
+        Ok(())
+    }
+
+    /// Adopts the Godot classes in the library that weren't created through
+    /// the cli by scanning the library's source files for them. Every class
+    /// found that isn't in the config is added to it and a gdns file is
+    /// created for it in the Godot project if it doesn't have one already.
+    /// The number of classes adopted is returned.
+    pub fn adopt(&mut self) -> Result<usize> {
+        log_info_to_console("[adopt] scanning library...");
+
+        let library_name_snake_case = self.config.name.to_case(Case::Snake);
+        let gdns_dir = get_gdns_dir(&self.config, &self.cargo_config);
+
+        let mut adopted_classes = 0;
+        for class in scan_library_classes(&self.dir, self.config.api) {
+            let module_name_snake_case = class.name.to_case(Case::Snake);
+
+            // The root module of a plugin is never added to the config so it
+            // doesn't need to be adopted.
+            let is_plugin_module =
+                self.config.is_plugin && module_name_snake_case == library_name_snake_case;
+            if is_plugin_module || is_module_in_config(&class.name, &mut self.config) {
+                continue;
+            }
+
+            // Classes that aren't in their own `src/<module>.rs` file have
+            // their file saved so that they can be found later.
+            let file_path = class.file_path.to_string_lossy().replace('\\', "/");
+            if file_path != format!("src/{}.rs", &module_name_snake_case) {
+                self.config
+                    .module_files
+                    .insert(class.name.to_owned(), file_path);
+            }
+
+            // GDExtension classes are registered with Godot automatically so
+            // there's no gdns file to create for them.
+            if self.config.api == Api::Gdnative
+                && find_gdns_files_in_godot_project(
+                    &module_name_snake_case,
+                    &self.config,
+                    &self.cargo_config,
+                )
+                .is_empty()
+            {
+                create_dir_all(&gdns_dir)
+                    .context("Unable to create directory for module file in Godot.")?;
+
+                let gdns_file = GdnsFile::new(&class.name, &get_gdnlib_path(&self.config));
+                gdns_file.write(gdns_dir.join(format!("{}.gdns", &module_name_snake_case)))?;
+            }
+
+            add_module_to_config(
+                &self.dir,
+                &class.name,
+                class.base_class.as_deref(),
+                &mut self.config,
+            )?;
+
+            log_info_to_console(&format!(
+                "[adopt] adopted {} from {}",
+                &class.name,
+                class.file_path.display()
+            ));
+            adopted_classes += 1;
+        }
+
+        log_success_to_console(&format!("[adopt] adopted {} classes", adopted_classes));
+
+        Ok(adopted_classes)
+    }
+
+    /// Checks that the config, the cargo config, the lib.rs file, and the
+    /// Godot project still agree with each other and reports any problems
+    /// found. Some problems can be fixed automatically if `fix` is set.
+    ///
+    /// # Arguments
+    ///
+    /// `fix` - Indicates whether the problems that can be fixed should be fixed or not.
+    pub fn doctor(&mut self, fix: bool) -> Result<()> {
+        log_info_to_console("[doctor] checking library...");
+
+        let problems = find_problems(&self.dir, &self.config, &self.cargo_config)?;
+
+        let mut unresolved_problems = 0;
+        let mut fixable_problems = 0;
+        for problem in &problems {
+            if fix && problem.is_fixable() {
+                problem.fix(&self.dir, &mut self.config)?;
+                log_success_to_console(&format!("[doctor] fixed: {}", problem));
+            } else {
+                log_error_to_console(&format!("[doctor] {}", problem));
+                unresolved_problems += 1;
+                if problem.is_fixable() {
+                    fixable_problems += 1;
+                }
+            }
+        }
+
+        if unresolved_problems > 0 {
+            if fixable_problems > 0 {
+                log_info_to_console(&format!(
+                    "[doctor] {} of the problems can be fixed with `godot-rust-cli doctor --fix`",
+                    fixable_problems
+                ));
+            }
+            return Err(Error::Invalid(format!(
+                "[doctor] found {} problems that need to be fixed",
+                unresolved_problems
+            )));
+        }
+
+        log_success_to_console("[doctor] no problems found");
+
+        Ok(())
+    }
+
+    /// Runs the command to build the library and then copies over the
+    /// dynamic libraries to the Godot project. If `watch` is set, an initial
+    /// build is run and then the Rust library is watched for changes to
+    /// rebuild.
+    ///
+    /// # Arguments
+    ///
+    /// `options` - The options to build the library with.
+    pub fn build(&self, options: BuildOptions) -> Result<()> {
+        // Normalize the name of the library as snake case as that is what is
+        // needed to construct the path to the dynamic library.
+        let library_name_snake_case = &self.config.name.to_case(Case::Snake);
+
+        // Build for the native platform by default.
+        let native_platform = consts::OS.to_lowercase();
+
+        if options.jobs == Some(0) {
+            return Err(Error::Invalid(
+                "At least one build has to be able to run at once".to_string(),
+            ));
+        }
+
+        // GDExtension libraries don't have a gdnlib file to point to the
+        // versioned dynamic libraries.
+        if options.versioned && self.config.api == Api::Gdextension {
+            return Err(Error::Invalid(
+                "Versioned builds can only be used for gdnative libraries".to_string(),
+            ));
+        }
+
+        let profile = self.get_profile_to_build(&options)?;
+        let cargo_args = self.get_cargo_args_to_build(&options);
+
+        let settings = BuildSettings {
+            library_dir: &self.dir,
+            rust_library_name: library_name_snake_case,
+            godot_project_absolute_path: &self.cargo_config.env.godot_project_path,
+            profile: &profile,
+            cargo_args: &cargo_args,
+            is_plugin: self.config.is_plugin,
+            is_versioned: options.versioned,
+            platform_backends: &self.config.platform_backends,
+            process_groups: None,
+        };
+
+        // The native platform is always built and the other platforms are
+        // built after it.
+        let other_platforms = self.get_other_platforms_to_build(&options, &native_platform)?;
+
+        if options.watch {
+            let jobs = options.jobs.unwrap_or(other_platforms.len());
+            return build_and_watch_for_changes(
+                &settings,
+                &native_platform,
+                &other_platforms,
+                jobs,
+                options.watch_delay,
+            );
+        }
+
+        log_info_to_console("[build] build starting...");
+
+        // Builds for several platforms run at the same time so their output
+        // goes to log files instead of the console.
+        if other_platforms.is_empty() {
+            build_for_platform(&settings, &native_platform)?;
+        } else {
+            let jobs = options.jobs.unwrap_or(other_platforms.len() + 1);
+            build_for_platforms(&settings, &native_platform, &other_platforms, jobs)?;
+        }
+
+        // Let the user know that the build is complete.
+        log_success_to_console("[build] build complete");
+
+        Ok(())
+    }
+
+    /// Returns the cargo profile to build with. The profile passed in the
+    /// options is used first, then the release profile if `release` is set,
+    /// and then the profile in the configuration or the `dev` profile.
+    ///
+    /// # Arguments
+    ///
+    /// `options` - The options the library is being built with.
+    fn get_profile_to_build(&self, options: &BuildOptions) -> Result<String> {
+        match (&options.profile, options.release) {
+            (Some(_), true) => Err(Error::Invalid(
+                "A profile can't be used for a release build, use the release profile instead"
+                    .to_string(),
+            )),
+            (Some(profile), false) if profile.trim().is_empty() => Err(Error::Invalid(
+                "The profile to build with can't be empty".to_string(),
+            )),
+            (Some(profile), false) => Ok(profile.trim().to_string()),
+            (None, true) => Ok("release".to_string()),
+            (None, false) => Ok(self
+                .config
+                .build_profile
+                .clone()
+                .unwrap_or_else(|| "dev".to_string())),
+        }
+    }
+
+    /// Returns the extra arguments to pass to cargo, which are the features
+    /// and arguments from the configuration followed by the arguments passed
+    /// in the options.
+    ///
+    /// # Arguments
+    ///
+    /// `options` - The options the library is being built with.
+    fn get_cargo_args_to_build(&self, options: &BuildOptions) -> Vec<String> {
+        let mut cargo_args: Vec<String> = vec![];
+        if !self.config.build_features.is_empty() {
+            cargo_args.push("--features".to_string());
+            cargo_args.push(self.config.build_features.join(","));
+        }
+        if self.config.build_no_default_features {
+            cargo_args.push("--no-default-features".to_string());
+        }
+        cargo_args.extend(self.config.build_cargo_args.iter().cloned());
+        cargo_args.extend(options.cargo_args.iter().cloned());
+
+        cargo_args
+    }
+
+    /// Returns the platforms other than the native one to build for, which
+    /// are every platform in the configuration if `all_platforms` is set or
+    /// the ones passed in `platforms` otherwise. The platforms passed have to
+    /// be in the configuration since they need to be set up to be built.
+    ///
+    /// # Arguments
+    ///
+    /// `options` - The options the library is being built with.
+    /// `native_platform` - The user's native platform.
+    fn get_other_platforms_to_build(
+        &self,
+        options: &BuildOptions,
+        native_platform: &str,
+    ) -> Result<Vec<String>> {
+        let platforms = if options.all_platforms {
+            self.config.platforms.clone()
+        } else {
+            let mut platforms = vec![];
+            for platform in &options.platforms {
+                let platform_normalized = platform.to_lowercase();
+                if !self.config.platforms.contains(&platform_normalized)
+                    && platform_normalized != native_platform
+                {
+                    return Err(Error::Invalid(format!(
+                        "The platform {} hasn't been added to the library, add it with the add-platform command first",
+                        platform
+                    )));
+                }
+                platforms.push(platform_normalized);
+            }
+            platforms
+        };
+
+        let mut other_platforms: Vec<String> = vec![];
+        for platform in platforms {
+            if platform != native_platform && !other_platforms.contains(&platform) {
+                other_platforms.push(platform);
+            }
+        }
+
+        Ok(other_platforms)
+    }
+
+    /// Adds a new platform to the platforms that godot-rust-cli will build
+    /// the library for.
+    ///
+    /// Note that at this time godot-rust-cli only supports 64-bit platforms
+    /// apart from the 32-bit Android ABIs.
+    ///
+    /// Platforms only need to be added if you are buliding for a different
+    /// platform than your native platform. For example, if you are developing
+    /// on Windows then you don't need to add Windows as a platfrom because the
+    /// library will automatically be built for your native platform.
+    ///
+    /// How a platform is built depends on its `BuildBackend`, which is
+    /// chosen by `Platform::get_build_backend`. Only platforms built with
+    /// cross need docker or podman, while the others need their target
+    /// installed with rustup along with whatever SDK the backend uses.
+    ///
+    /// The list of platforms that can be provided are:
+    /// linux
+    /// windows
+    /// macos
+    /// macos-universal
+    /// android.arm64
+    /// android.armv7
+    /// android.x86_64
+    /// android.x86
+    /// ios
+    /// html5
+    ///
+    /// `android` adds every Android platform that wasn't added yet and
+    /// `android.arm` adds `android.arm64`, which it was renamed to.
+    ///
+    /// Any platform can be built with another backend instead, `cargo`,
+    /// `cross`, `zigbuild`, or `local-linker`, which is saved in the
+    /// configuration. Only the `cross` backend needs docker.
+    ///
+    /// If you would like another platform to be added then please open an
+    /// issue in the GitHub or let me know in the Discord.
+    ///
+    /// # Arguments
+    ///
+    /// `platform` - The platform to compile for.
+    /// `backend` - The backend to build the platform with instead of its default one, if any.
+    pub fn add_platform(&mut self, platform: &str, backend: Option<PlatformBackend>) -> Result<()> {
+        let mut platforms_to_add = get_platforms(platform);
+        if platforms_to_add.is_empty() {
+            return Err(Error::Invalid(format!(
+                "The target {} isn't a valid target, expected one of {}. Please file an issue in the GitHub or Discord if this is incorrect.",
+                &platform,
+                get_platform_names()
+            )));
+        }
+
+        // An alias that stands for several platforms, like `android`, only
+        // adds the ones that weren't added yet.
+        if platforms_to_add.len() > 1 {
+            let (added_platforms, platforms_not_added): (Vec<&Platform>, Vec<&Platform>) =
+                platforms_to_add.into_iter().partition(|platform_to_add| {
+                    is_platform_in_config(platform_to_add.name, &mut self.config)
+                });
+            if platforms_not_added.is_empty() {
+                return Err(Error::Invalid(format!(
+                    "[add-platform] Every platform that {} stands for is already in the config.",
+                    &platform
+                )));
+            }
+            for added_platform in added_platforms {
+                log_info_to_console(&format!(
+                    "[add-platform] {} is already in the config, skipping it",
+                    added_platform.name
+                ));
+            }
+            platforms_to_add = platforms_not_added;
+        }
+
+        for platform_to_add in platforms_to_add {
+            self.add_platform_to_project(platform_to_add, backend)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a platform to the configuration and sets up what its build
+    /// backend needs.
+    ///
+    /// # Arguments
+    ///
+    /// `platform_to_add` - The platform to add.
+    /// `backend` - The backend to build the platform with instead of its default one, if any.
+    fn add_platform_to_project(
+        &mut self,
+        platform_to_add: &'static Platform,
+        backend: Option<PlatformBackend>,
+    ) -> Result<()> {
+        // Add the platform to the `platforms` array in the config along with
+        // the backend chosen for it.
+        add_platform_to_config(&self.dir, platform_to_add.name, backend, &mut self.config)?;
+
+        // Only platforms built with cross need docker so nothing is set up
+        // for cross when another backend is chosen.
+        let build_backend = platform_to_add.get_build_backend(&self.config.platform_backends);
+        match (build_backend, platform_to_add.docker_image) {
+            // Since some platforms need images that do more than the default
+            // cross images, we have to copy the docker file override into the
+            // user's library and add it.
+            (BuildBackend::Cross, Some(_)) => {
+                add_image_override_for_platform(&self.dir, platform_to_add)
+            }
+            (BuildBackend::Cross, None) => Ok(()),
+            // The Emscripten SDK on the host is used if there is one,
+            // otherwise the docker image with it is built for cross.
+            (BuildBackend::Emscripten, _) => match find_emcc() {
+                Some(emcc_path) => {
+                    log_info_to_console(&format!(
+                        "{} is built with {} and needs the nightly toolchain with `rustup toolchain install nightly --component rust-src` and `rustup target add --toolchain nightly {}`",
+                        platform_to_add.name,
+                        emcc_path.display(),
+                        platform_to_add.rust_triples.join(" ")
+                    ));
+                    Ok(())
+                }
+                None => add_image_override_for_platform(&self.dir, platform_to_add),
+            },
+            (BuildBackend::AndroidNdk { .. }, _) => {
+                log_info_to_console(&format!(
+                    "{} is built with the Android NDK from ANDROID_NDK_HOME or ANDROID_SDK_ROOT so its targets have to be installed with `rustup target add {}`",
+                    platform_to_add.name,
+                    platform_to_add.rust_triples.join(" ")
+                ));
+                Ok(())
+            }
+            (BuildBackend::Zigbuild, _) => {
+                log_info_to_console(&format!(
+                    "{} is built with cargo-zigbuild so it has to be installed with `cargo install cargo-zigbuild` along with zig and its targets have to be installed with `rustup target add {}`",
+                    platform_to_add.name,
+                    platform_to_add.rust_triples.join(" ")
+                ));
+                Ok(())
+            }
+            (BuildBackend::LocalLinker, _) => {
+                for rust_triple in platform_to_add.rust_triples {
+                    if find_target_linker(&self.dir, rust_triple).is_none() {
+                        log_warning_to_console(&format!(
+                            "No linker is configured for {} yet, set `linker` under `[target.{}]` in .cargo/config.toml before building {}",
+                            rust_triple, rust_triple, platform_to_add.name
+                        ));
+                    }
+                }
+                log_info_to_console(&format!(
+                    "{} is built with the linkers in .cargo/config.toml so its targets have to be installed with `rustup target add {}`",
+                    platform_to_add.name,
+                    platform_to_add.rust_triples.join(" ")
+                ));
+                Ok(())
+            }
+            (BuildBackend::Cargo, _) => {
+                log_info_to_console(&format!(
+                    "{} is built with cargo so its targets have to be installed with `rustup target add {}`",
+                    platform_to_add.name,
+                    platform_to_add.rust_triples.join(" ")
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes a platform from the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// `platform` - The platform to remove.
+    pub fn remove_platform(&mut self, platform: &str) -> Result<()> {
+        // The backend has to be known before the platform is removed since
+        // the backend chosen for it is removed along with it.
+        let platform_to_remove = get_platform(platform).map(|platform_to_remove| {
+            (
+                platform_to_remove,
+                platform_to_remove.get_build_backend(&self.config.platform_backends),
+            )
+        });
+
+        // Remove the platform from the `platforms` array in the configuration.
+        remove_platform_from_config_if_exists(&self.dir, platform, &mut self.config)?;
+
+        // Remove the docker images from the user's system since they're no
+        // longer needed. Only platforms built with cross use the default
+        // cross images, and HTML5 only has an image if there wasn't an
+        // Emscripten SDK on the host when it was added.
+        let image_tags: Vec<String> = match platform_to_remove {
+            Some((platform_to_remove, BuildBackend::Cross)) => platform_to_remove
+                .rust_triples
+                .iter()
+                .map(|rust_triple| format!("rustembedded/cross:{}", rust_triple))
+                .chain(
+                    platform_to_remove
+                        .docker_image
+                        .map(|docker_image| docker_image.tag.to_owned()),
+                )
+                .collect(),
+            Some((platform_to_remove, BuildBackend::Emscripten)) => platform_to_remove
+                .docker_image
+                .map(|docker_image| docker_image.tag.to_owned())
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let removed_images = image_tags
+            .iter()
+            .filter(|image_tag| remove_docker_image_if_exists(image_tag))
+            .count();
+        if removed_images > 0 {
+            log_info_to_console(&format!("Removed docker image for {}", &platform));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the directory in the Godot project that gdns files are created
+/// in. This is the gdnative directory at the root of the Godot project for
+/// normal libraries or the gdnative directory in the plugin's directory for
+/// plugin libraries.
+///
+/// # Arguments
+///
+/// `config` - The configuration of the library.
+/// `cargo_config` - The cargo configuration of the library.
+fn get_gdns_dir(config: &Config, cargo_config: &CargoConfig) -> PathBuf {
+    if config.is_plugin {
+        PathBuf::from(&cargo_config.env.godot_project_path)
+            .join("addons")
+            .join(config.name.to_case(Case::Snake))
+            .join("gdnative")
+    } else {
+        PathBuf::from(&cargo_config.env.godot_project_path).join("gdnative")
+    }
+}
+
+/// Returns the code for a new GDNative module that inherits from a Godot
+/// class.
+///
+/// # Arguments
+///
+/// `module_name_pascal_case` - The PascalCase name of the module.
+/// `godot_class` - The Godot class that the module inherits from.
+/// `is_plugin` - Indicates whether the library is for a plugin or not.
+fn create_gdnative_module_scope(
+    module_name_pascal_case: &str,
+    godot_class: &GodotClass,
+    is_plugin: bool,
+) -> Scope {
+    let owner_type = format!("&{}", godot_class.name);
+
+    let mut scope = Scope::new();
+    scope.import(GodotClass::import_path(Api::Gdnative), godot_class.name);
+    if godot_class.kind == GodotClassKind::Control {
+        scope.import(GodotClass::import_path(Api::Gdnative), "InputEvent");
+    }
+    scope.import("gdnative::prelude", "*");
+
+    // Plugin modules are tool classes so they need to use user data that can
+    // be accessed from the editor.
+    let script_struct = scope.new_struct(module_name_pascal_case);
+    script_struct.vis("pub");
+    script_struct.attr(&format!("#[inherit({})]", godot_class.name));
+    if is_plugin {
+        script_struct.derive("gdnative::NativeClass");
+        script_struct.attr(&format!(
+            "#[user_data(user_data::LocalCellData<{}>)]",
+            module_name_pascal_case
+        ));
+    } else {
+        script_struct.derive("NativeClass");
+    }
+
+    let script_impl = scope.new_impl(module_name_pascal_case);
+    if is_plugin {
+        script_impl.r#macro("#[gdnative::methods]");
+    } else {
+        script_impl.r#macro("#[methods]");
+    }
+
+    let new_fn = script_impl.new_fn("new");
+    new_fn.arg("_owner", &owner_type);
+    new_fn.ret("Self");
+    new_fn.line(format!("{} {}", module_name_pascal_case, "{}"));
+
+    // Resources and references aren't in the scene tree so they don't get
+    // any of the lifecycle methods.
+    if godot_class.kind == GodotClassKind::Resource {
+        return scope;
+    }
+
+    let ready_fn = script_impl.new_fn("_ready");
+    ready_fn.attr("export");
+    ready_fn.arg_mut_self();
+    ready_fn.arg("_owner", &owner_type);
+    ready_fn.line("godot_print!(\"Hello world!\")");
+
+    let (lifecycle_fn_name, lifecycle_arg) = match godot_class.kind {
+        GodotClassKind::Node => ("_process", ("_delta", "f32")),
+        GodotClassKind::PhysicsBody => ("_physics_process", ("_delta", "f32")),
+        GodotClassKind::Control => ("_gui_input", ("_event", "Ref<InputEvent>")),
+        GodotClassKind::EditorPlugin | GodotClassKind::Resource => return scope,
+    };
+
+    let lifecycle_fn = script_impl.new_fn(lifecycle_fn_name);
+    lifecycle_fn.attr("export");
+    lifecycle_fn.arg_mut_self();
+    lifecycle_fn.arg("_owner", &owner_type);
+    lifecycle_fn.arg(lifecycle_arg.0, lifecycle_arg.1);
+
+    scope
+}
+
+/// Returns the code for a new GDExtension module that inherits from a Godot
+/// class.
+///
+/// # Arguments
+///
+/// `module_name_pascal_case` - The PascalCase name of the module.
+/// `godot_class` - The Godot class that the module inherits from.
+fn create_gdextension_module_scope(
+    module_name_pascal_case: &str,
+    godot_class: &GodotClass,
+) -> Scope {
+    let base_type = format!("Base<{}>", godot_class.name);
+    let interface_trait = format!("I{}", godot_class.name);
+
+    let mut scope = Scope::new();
+    scope.import(GodotClass::import_path(Api::Gdextension), godot_class.name);
+    scope.import(GodotClass::import_path(Api::Gdextension), &interface_trait);
+    if godot_class.kind == GodotClassKind::Control {
+        scope.import(GodotClass::import_path(Api::Gdextension), "InputEvent");
+    }
+    scope.import("godot::prelude", "*");
+
+    // The derive is added as an attribute so that it comes before the
+    // `class` helper attribute that it introduces. Editor plugins have to be
+    // tool classes so that they run in the editor.
+    let class_attr = if godot_class.kind == GodotClassKind::EditorPlugin {
+        format!("#[class(tool, base={})]", godot_class.name)
+    } else {
+        format!("#[class(base={})]", godot_class.name)
+    };
+    let script_struct = scope.new_struct(module_name_pascal_case);
+    script_struct.vis("pub");
+    script_struct.attr("#[derive(GodotClass)]");
+    script_struct.attr(&class_attr);
+    script_struct.field("base", &base_type);
+
+    let script_impl = scope.new_impl(module_name_pascal_case);
+    script_impl.impl_trait(&interface_trait);
+    script_impl.r#macro("#[godot_api]");
+
+    let init_fn = script_impl.new_fn("init");
+    init_fn.arg("base", &base_type);
+    init_fn.ret("Self");
+    init_fn.line("Self { base }");
+
+    // Resources and reference counted objects aren't in the scene tree so
+    // they don't get any of the lifecycle methods.
+    if godot_class.kind == GodotClassKind::Resource {
+        return scope;
+    }
+
+    let ready_fn = script_impl.new_fn("ready");
+    ready_fn.arg_mut_self();
+    ready_fn.line("godot_print!(\"Hello world!\")");
+
+    let (lifecycle_fn_name, lifecycle_arg) = match godot_class.kind {
+        GodotClassKind::Node => ("process", ("_delta", "f64")),
+        GodotClassKind::PhysicsBody => ("physics_process", ("_delta", "f64")),
+        GodotClassKind::Control => ("gui_input", ("_event", "Gd<InputEvent>")),
+        GodotClassKind::EditorPlugin | GodotClassKind::Resource => return scope,
+    };
+
+    let lifecycle_fn = script_impl.new_fn(lifecycle_fn_name);
+    lifecycle_fn.arg_mut_self();
+    lifecycle_fn.arg(lifecycle_arg.0, lifecycle_arg.1);
+
+    scope
+}
+
+/// Removes a module's gdns file from the Godot project.
+///
+/// # Arguments
+///
+/// `module_name_snake_case` - The snake_case name of the module to remove the gdns file of.
+/// `config` - The configuration of the library.
+/// `cargo_config` - The cargo configuration of the library.
+fn remove_gdns_file_from_godot_project(
+    module_name_snake_case: &str,
+    config: &Config,
+    cargo_config: &CargoConfig,
+) -> Result<()> {
+    for gdns_path in find_gdns_files_in_godot_project(module_name_snake_case, config, cargo_config)
+    {
+        remove_file(gdns_path).context("Unable to remove the module's gdns file from the Godot project while destroying the module")?;
+    }
+
+    Ok(())
+}
+
+/// Returns the paths to a module's gdns file in the Godot project. The gdns
+/// file is first looked for where it was created and if it was moved, the
+/// Godot project is searched for it.
+///
+/// # Arguments
+///
+/// `module_name_snake_case` - The snake_case name of the module to find the gdns file of.
+/// `config` - The configuration of the library.
+/// `cargo_config` - The cargo configuration of the library.
+fn find_gdns_files_in_godot_project(
+    module_name_snake_case: &str,
+    config: &Config,
+    cargo_config: &CargoConfig,
+) -> Vec<PathBuf> {
+    let library_name_snake_case = &config.name.to_case(Case::Snake);
+    let gdns_file_name = format!("{}.gdns", &module_name_snake_case);
+
+    // The first place we should check for the module is either the gdnative
+    // folder in the plugin directory if it's a plugin or just the gdnative
+    // folder in the root directory of the Godot project otherwise.
+    let possible_gdns_path = if config.is_plugin {
+        PathBuf::from(&cargo_config.env.godot_project_path)
+            .join("addons")
+            .join(library_name_snake_case)
+            .join("gdnative")
+            .join(&gdns_file_name)
+    } else {
+        PathBuf::from(&cargo_config.env.godot_project_path)
+            .join("gdnative")
+            .join(&gdns_file_name)
+    };
+
+    if possible_gdns_path.exists() {
+        // If this path exists, then it's the only one we need.
+        return vec![possible_gdns_path];
+    }
+
+    // Otherwise, we want to search a directory for the module. If the module
+    // is a plugin, we can limit our search to the plugin directory.
+    // Otherwise, we search the entire project since the user might have moved
+    // it around.
+    let search_dir = if config.is_plugin {
+        PathBuf::from(&cargo_config.env.godot_project_path)
+            .join("addons")
+            .join(library_name_snake_case)
+    } else {
+        PathBuf::from(&cargo_config.env.godot_project_path)
+    };
+
+    WalkDir::new(search_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_name().to_str() == Some(gdns_file_name.as_str()))
+        .map(|entry| entry.path().to_path_buf())
+        .collect()
+}
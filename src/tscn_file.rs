@@ -0,0 +1,72 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::error::{Context, Result};
+
+/// The structure of a tscn file that is used to describe a scene with a
+/// single root node.
+#[derive(Debug)]
+pub struct TscnFile {
+    /// The name of the root node.
+    pub name: String,
+    /// The type of the root node.
+    pub node_type: String,
+    /// The path to the gdns file used as the root node's script. GDExtension
+    /// classes are node types themselves so they don't have a script.
+    pub script_path: Option<String>,
+}
+
+impl TscnFile {
+    /// Creates a new instance of the TscnFile with the provided properties.
+    ///
+    /// # Arguments
+    ///
+    /// `name` - The pascal case version of the module name.
+    /// `node_type` - The type of the root node.
+    /// `script_path` - The path to the gdns file, relative to the root of the Godot project.
+    pub fn new(name: &str, node_type: &str, script_path: Option<&str>) -> TscnFile {
+        TscnFile {
+            name: name.to_string(),
+            node_type: node_type.to_string(),
+            script_path: script_path.map(|path| path.to_string()),
+        }
+    }
+
+    /// Writes the provided TscnFile to the tscn file.
+    ///
+    /// # Arguments
+    ///
+    /// `path` - The path to write the file to.
+    pub fn write(&self, path: PathBuf) -> Result<()> {
+        std::fs::write(path, self.to_string()).context("Unable to update contents of the tscn file")
+    }
+}
+
+impl fmt::Display for TscnFile {
+    /// Writes the TscnFile as a pretty printed string. Scenes with a script
+    /// are written in the Godot 3 format and scenes without one are written
+    /// in the Godot 4 format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.script_path {
+            Some(script_path) => write!(
+                f,
+                r#"[gd_scene load_steps=2 format=2]
+
+[ext_resource path="res://{}" type="Script" id=1]
+
+[node name="{}" type="{}"]
+script = ExtResource( 1 )
+"#,
+                script_path, self.name, self.node_type
+            ),
+            None => write!(
+                f,
+                r#"[gd_scene format=3]
+
+[node name="{}" type="{}"]
+"#,
+                self.name, self.node_type
+            ),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use std::env;
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Error, Result};
+use crate::platforms::Platform;
+
+/// The lowest Android API level that the library is linked against. This is
+/// the lowest level that Godot supports and every NDK still ships.
+const ANDROID_API_LEVEL: u32 = 21;
+
+/// Returns the path to the Android NDK. `ANDROID_NDK_HOME` is used if it's
+/// set, otherwise the NDK is looked for in the Android SDK at
+/// `ANDROID_SDK_ROOT` or `ANDROID_HOME`, preferring the newest version in its
+/// `ndk` directory over the older `ndk-bundle` directory.
+pub fn get_android_ndk_dir() -> Result<PathBuf> {
+    if let Some(ndk_dir) = get_env_dir("ANDROID_NDK_HOME") {
+        return if ndk_dir.exists() {
+            Ok(ndk_dir)
+        } else {
+            Err(Error::Invalid(format!(
+                "ANDROID_NDK_HOME is set to {} which doesn't exist",
+                ndk_dir.display()
+            )))
+        };
+    }
+
+    let sdk_dir = get_env_dir("ANDROID_SDK_ROOT").or_else(|| get_env_dir("ANDROID_HOME"));
+    let ndk_dir = sdk_dir.and_then(|sdk_dir| {
+        get_newest_ndk_in_sdk(&sdk_dir).or_else(|| {
+            let ndk_bundle_dir = sdk_dir.join("ndk-bundle");
+            if ndk_bundle_dir.exists() {
+                Some(ndk_bundle_dir)
+            } else {
+                None
+            }
+        })
+    });
+
+    ndk_dir.ok_or_else(|| {
+        Error::Invalid(
+            "Unable to find the Android NDK, set ANDROID_NDK_HOME to the NDK's directory or ANDROID_SDK_ROOT to an Android SDK that has the NDK installed".to_string(),
+        )
+    })
+}
+
+/// Sets the linker, C compiler, and archiver that cargo and any build
+/// scripts use for each of the platform's targets to the ones in the Android
+/// NDK so that the platform can be built without docker.
+///
+/// # Arguments
+///
+/// `build_command` - The command that builds the library.
+/// `platform` - The Android platform that's being built.
+/// `clang_target` - The target that the NDK's clang wrappers are named after.
+pub fn add_android_ndk_env(
+    build_command: &mut Command,
+    platform: &Platform,
+    clang_target: &str,
+) -> Result<()> {
+    let ndk_bin_dir = get_android_ndk_dir()?
+        .join("toolchains")
+        .join("llvm")
+        .join("prebuilt")
+        .join(get_ndk_host_tag())
+        .join("bin");
+
+    // The clang wrappers for each API level are shell scripts on Unix and
+    // batch files on Windows.
+    let clang_ext = if cfg!(windows) { ".cmd" } else { "" };
+    let linker_path = ndk_bin_dir.join(format!(
+        "{}{}-clang{}",
+        clang_target, ANDROID_API_LEVEL, clang_ext
+    ));
+    if !linker_path.exists() {
+        return Err(Error::Invalid(format!(
+            "Unable to find the linker for {} in the Android NDK at {}",
+            platform.name,
+            linker_path.display()
+        )));
+    }
+    let ar_path = ndk_bin_dir.join(format!("llvm-ar{}", env::consts::EXE_SUFFIX));
+
+    for rust_triple in platform.rust_triples {
+        let cargo_target = rust_triple.to_uppercase().replace('-', "_");
+        let cc_target = rust_triple.replace('-', "_");
+        build_command
+            .env(
+                format!("CARGO_TARGET_{}_LINKER", cargo_target),
+                &linker_path,
+            )
+            .env(format!("CC_{}", cc_target), &linker_path)
+            .env(format!("AR_{}", cc_target), &ar_path);
+    }
+
+    Ok(())
+}
+
+/// Returns the value of an environment variable as a path if it's set and
+/// isn't empty.
+///
+/// # Arguments
+///
+/// `name` - The name of the environment variable.
+fn get_env_dir(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Returns the newest version of the NDK installed side by side in the
+/// Android SDK's `ndk` directory, if there are any.
+///
+/// # Arguments
+///
+/// `sdk_dir` - The path to the Android SDK.
+fn get_newest_ndk_in_sdk(sdk_dir: &Path) -> Option<PathBuf> {
+    read_dir(sdk_dir.join("ndk"))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .max_by_key(|path| {
+            // Versions are compared by their numbers so that 25.10.0 is
+            // newer than 25.2.9519653.
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+                .split('.')
+                .map(|part| part.parse::<u64>().unwrap_or(0))
+                .collect::<Vec<u64>>()
+        })
+}
+
+/// Returns the name of the directory in the NDK's prebuilt toolchains for
+/// the host. The NDK only ships x86_64 toolchains, which run on Apple
+/// Silicon through Rosetta.
+fn get_ndk_host_tag() -> &'static str {
+    match env::consts::OS {
+        "macos" => "darwin-x86_64",
+        "windows" => "windows-x86_64",
+        _ => "linux-x86_64",
+    }
+}
@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::env::current_dir;
+use std::collections::BTreeMap;
 use std::fs::read_to_string;
 use std::fs::write;
 use std::path::{Path, PathBuf};
-use std::process::exit;
+use std::str::FromStr;
 
-use crate::log_utils::{log_error_to_console, log_info_to_console, log_success_to_console};
+use crate::error::{Context, Error, Result};
+use crate::log_utils::log_success_to_console;
 use convert_case::{Case, Casing};
 
 /// The stucture of the configuration file.
@@ -29,78 +30,244 @@ pub struct Config {
     /// Tracks the modules created and destroyed through the cli.
     /// Added v0.1.0
     pub modules: Vec<String>,
+    /// The Godot API that the library's modules are generated for. Libraries
+    /// created before this was added are always GDNative libraries.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub api: Api,
+    /// The Godot class that each module inherits from. Modules created before
+    /// this was added don't have an entry.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub module_base_classes: BTreeMap<String, String>,
+    /// The path, relative to the Godot project, of the scene created for
+    /// each module that was created with a scene.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub module_scenes: BTreeMap<String, String>,
+    /// The path, relative to the library, of the file that each adopted
+    /// module is in if it isn't `src/<module>.rs`.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub module_files: BTreeMap<String, String>,
+    /// The cargo profile that builds use when neither `--release` nor
+    /// `--profile` is passed to the `build` command.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub build_profile: Option<String>,
+    /// The features that builds enable.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub build_features: Vec<String>,
+    /// Indicates whether builds disable the library's default features.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub build_no_default_features: bool,
+    /// The extra arguments that builds pass to cargo before the ones passed
+    /// to the `build` command.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub build_cargo_args: Vec<String>,
+    /// The build backend chosen for each platform that isn't built with the
+    /// platform's default backend.
+    /// Added v0.5.0
+    #[serde(default)]
+    pub platform_backends: BTreeMap<String, PlatformBackend>,
+}
+
+/// The Godot APIs that a library can be created for.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Api {
+    /// Godot 3 NativeScript modules using the `gdnative` crate.
+    #[default]
+    Gdnative,
+    /// Godot 4 extension classes using the `godot` crate.
+    Gdextension,
+}
+
+impl FromStr for Api {
+    type Err = String;
+
+    fn from_str(api: &str) -> std::result::Result<Self, Self::Err> {
+        match api.to_lowercase().as_str() {
+            "gdnative" => Ok(Api::Gdnative),
+            "gdextension" => Ok(Api::Gdextension),
+            _ => Err(format!(
+                "{} isn't a valid api, expected gdnative or gdextension",
+                api
+            )),
+        }
+    }
+}
+
+/// The build backends that can be chosen for a platform instead of its
+/// default one.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlatformBackend {
+    /// `cargo build --target` with whatever linker cargo finds for the
+    /// target.
+    Cargo,
+    /// `cross build` in a docker container.
+    Cross,
+    /// `cargo zigbuild --target`, which links with zig instead of the
+    /// target's linker.
+    Zigbuild,
+    /// `cargo build --target` with the linker configured for the target in
+    /// `.cargo/config.toml`.
+    LocalLinker,
+}
+
+impl FromStr for PlatformBackend {
+    type Err = String;
+
+    fn from_str(backend: &str) -> std::result::Result<Self, Self::Err> {
+        match backend.to_lowercase().as_str() {
+            "cargo" => Ok(PlatformBackend::Cargo),
+            "cross" => Ok(PlatformBackend::Cross),
+            "zigbuild" => Ok(PlatformBackend::Zigbuild),
+            "local-linker" => Ok(PlatformBackend::LocalLinker),
+            _ => Err(format!(
+                "{} isn't a valid backend, expected cargo, cross, zigbuild, or local-linker",
+                backend
+            )),
+        }
+    }
 }
 
 /// Returns the path to the configuration file.
-pub fn get_path_to_config_file() -> PathBuf {
-    let curr_dir = current_dir().expect("Unable to get current directory");
-    return Path::new(&curr_dir).join("godot-rust-cli.json");
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+pub fn get_path_to_config_file(library_dir: &Path) -> PathBuf {
+    library_dir.join("godot-rust-cli.json")
+}
+
+/// Returns the path to the library's gdnlib file in the Godot project. The
+/// path is relative to the root of the Godot project and doesn't include the
+/// extension since that's how gdns files refer to it.
+///
+/// # Arguments
+///
+/// `config` - The configuration of the library.
+pub fn get_gdnlib_path(config: &Config) -> String {
+    let library_name_snake_case = config.name.to_case(Case::Snake);
+
+    if config.is_plugin {
+        format!(
+            "addons/{}/gdnative/{}",
+            &library_name_snake_case, &library_name_snake_case
+        )
+    } else {
+        format!("gdnative/{}", &library_name_snake_case)
+    }
+}
+
+/// Returns the path, relative to the library, of the file that a module is
+/// in. This is `src/<module>.rs` unless the module was adopted from another
+/// file.
+///
+/// # Arguments
+///
+/// `module_name` - The name of the module.
+/// `config` - The configuration of the library.
+pub fn get_module_file_path(module_name: &str, config: &Config) -> PathBuf {
+    match config.module_files.get(module_name) {
+        Some(file_path) => PathBuf::from(file_path),
+        None => Path::new("src").join(format!("{}.rs", module_name.to_case(Case::Snake))),
+    }
 }
 
 /// Creates the initial configuration and saves it to a json file.
 ///
 /// # Arguments
 ///
+/// `library_dir` - The path to the library's directory.
 /// `library_name` - The name of the library.
 /// `godot_project_dir_name` - The name of the Godot project.
 /// `is_library` - Indicates whether the library is for a plugin or not.
+/// `api` - The Godot API that the library is for.
 pub fn create_initial_config(
+    library_dir: &Path,
     library_name: String,
     godot_project_dir_name: String,
     is_plugin: bool,
-) -> Config {
+    api: Api,
+) -> Result<Config> {
     let cli_version = env!("CARGO_PKG_VERSION").to_string();
     let config = Config {
         name: library_name,
         cli_version,
-        godot_project_dir_name: godot_project_dir_name,
-        is_plugin: is_plugin,
+        godot_project_dir_name,
+        is_plugin,
         platforms: vec![],
         modules: vec![],
+        api,
+        module_base_classes: BTreeMap::new(),
+        module_scenes: BTreeMap::new(),
+        module_files: BTreeMap::new(),
+        build_profile: None,
+        build_features: vec![],
+        build_no_default_features: false,
+        build_cargo_args: vec![],
+        platform_backends: BTreeMap::new(),
     };
     let config_as_json =
-        serde_json::to_string_pretty(&config).expect("Unable to create initial configuration");
+        serde_json::to_string_pretty(&config).context("Unable to create initial configuration")?;
 
-    write("godot-rust-cli.json", config_as_json).expect("Unable to create configuration file");
+    write(get_path_to_config_file(library_dir), config_as_json)
+        .context("Unable to create configuration file")?;
 
-    return config;
+    Ok(config)
 }
 
 /// Returns the configuration as an object that can be operated on.
-pub fn get_config_as_object() -> Config {
-    let config_file_path = get_path_to_config_file();
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+pub fn get_config_as_object(library_dir: &Path) -> Result<Config> {
+    let config_file_path = get_path_to_config_file(library_dir);
+    if !config_file_path.exists() {
+        return Err(Error::ConfigMissing(config_file_path));
+    }
+
     let config_as_string =
-        read_to_string(config_file_path).expect("Unable to read configuration file");
+        read_to_string(config_file_path).context("Unable to read configuration file")?;
 
-    return serde_json::from_str(&config_as_string).expect("Unable to parse configuration file");
+    serde_json::from_str(&config_as_string).context("Unable to parse configuration file")
 }
 
 /// Saves the configuration file to the library directory.
 ///
 /// # Arguments
 ///
+/// `library_dir` - The path to the library's directory.
 /// `config` - The configuration to save.
-pub fn save_config_to_file(config: &mut Config) {
-    let config_file_path = get_path_to_config_file();
+pub fn save_config_to_file(library_dir: &Path, config: &mut Config) -> Result<()> {
+    let config_file_path = get_path_to_config_file(library_dir);
     let config_as_string =
-        serde_json::to_string_pretty(&config).expect("Unable to parse configuration");
+        serde_json::to_string_pretty(&config).context("Unable to parse configuration")?;
 
-    match write(config_file_path, config_as_string) {
-        Ok(_) => (),
-        Err(e) => {
-            log_error_to_console(&e.to_string());
-            exit(1);
-        }
-    }
+    write(config_file_path, config_as_string).context("Unable to save configuration file")
 }
 
 /// Adds a module to the configuration file and saves it.
 ///
 /// # Arguments
 ///
+/// `library_dir` - The path to the library's directory.
 /// `module_name` - The name of the module to add to the configuration file.
+/// `base_class` - The Godot class that the module inherits from, if it's known.
 /// `config` - Can be passed if the config is already in memory.
-pub fn add_module_to_config(module_name: &str, config: &mut Config) {
+pub fn add_module_to_config(
+    library_dir: &Path,
+    module_name: &str,
+    base_class: Option<&str>,
+    config: &mut Config,
+) -> Result<()> {
     // If the library is for a plugin, and the module is the root plugin module,
     // we don't add it to the config since it can't be removed.
     if config.is_plugin {
@@ -108,12 +275,17 @@ pub fn add_module_to_config(module_name: &str, config: &mut Config) {
         let module_name_snake_case = &module_name.to_case(Case::Snake);
 
         if module_name_snake_case == config_name_snake_case {
-            return;
+            return Ok(());
         }
     }
 
     config.modules.push(module_name.to_string());
-    save_config_to_file(config);
+    if let Some(base_class) = base_class {
+        config
+            .module_base_classes
+            .insert(module_name.to_string(), base_class.to_string());
+    }
+    save_config_to_file(library_dir, config)
 }
 
 /// Indicates whether a module is present in the config or not.
@@ -123,56 +295,124 @@ pub fn add_module_to_config(module_name: &str, config: &mut Config) {
 /// `module_name` - The module to check if exists or not.
 /// `config` - The configuration file.
 pub fn is_module_in_config(module_name: &str, config: &mut Config) -> bool {
-    return config.modules.iter().any(|i| i == module_name);
+    config.modules.iter().any(|i| i == module_name)
 }
 
 /// Removes a module from the config file if it exists.
 ///
 /// # Arguments
 ///
+/// `library_dir` - The path to the library's directory.
 /// `module_name` - The name of the module to remove from the config file.
 /// `config` - The configuration file.
-pub fn remove_module_from_config_if_exists(module_name: &str, config: &mut Config) {
-    let module_exists_in_config = is_module_in_config(&module_name, config);
+pub fn remove_module_from_config_if_exists(
+    library_dir: &Path,
+    module_name: &str,
+    config: &mut Config,
+) -> Result<()> {
+    // Modules are saved by their PascalCase name but older versions saved
+    // them by the name they were created with so either one is removed.
+    let module_name_pascal_case = module_name.to_case(Case::Pascal);
+    let index = match config
+        .modules
+        .iter()
+        .position(|x| x.to_case(Case::Pascal) == module_name_pascal_case)
+    {
+        Some(index) => index,
+        None => {
+            return Err(Error::Invalid(
+                "The module to remove doesn't exist".to_string(),
+            ))
+        }
+    };
+    let removed_module_name = config.modules.remove(index);
+    config.module_base_classes.remove(&removed_module_name);
+    config.module_scenes.remove(&module_name_pascal_case);
+    config.module_files.remove(&removed_module_name);
+
+    save_config_to_file(library_dir, config)
+}
+
+/// Renames a module in the config file, keeping its place in the modules.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `old_module_name` - The current name of the module.
+/// `new_module_name` - The name to rename the module to.
+/// `config` - The configuration file.
+pub fn rename_module_in_config(
+    library_dir: &Path,
+    old_module_name: &str,
+    new_module_name: &str,
+    config: &mut Config,
+) -> Result<()> {
+    for module_name in config.modules.iter_mut() {
+        if module_name == old_module_name {
+            *module_name = new_module_name.to_string();
+        }
+    }
 
-    if !module_exists_in_config {
-        log_info_to_console("The module to remove doesn't exist.");
-        exit(1);
+    if let Some(base_class) = config.module_base_classes.remove(old_module_name) {
+        config
+            .module_base_classes
+            .insert(new_module_name.to_string(), base_class);
     }
 
-    let index = config
-        .modules
-        .iter()
-        .position(|x| *x == module_name)
-        .unwrap();
-    config.modules.remove(index);
+    if let Some(scene_path) = config
+        .module_scenes
+        .remove(&old_module_name.to_case(Case::Pascal))
+    {
+        config
+            .module_scenes
+            .insert(new_module_name.to_case(Case::Pascal), scene_path);
+    }
+
+    if let Some(file_path) = config.module_files.remove(old_module_name) {
+        config
+            .module_files
+            .insert(new_module_name.to_string(), file_path);
+    }
 
-    save_config_to_file(config);
+    save_config_to_file(library_dir, config)
 }
 
 /// Adds a platform to the config file if it doesn't already exist.
 ///
 /// # Arguments
 ///
+/// `library_dir` - The path to the library's directory.
 /// `platform` - The platform to add to the configuration file.
+/// `backend` - The backend to build the platform with, if it isn't the platform's default one.
 /// `config` - Can be passed if the config is already in memory.
-pub fn add_platform_to_config(platform: &str, config: &mut Config) {
+pub fn add_platform_to_config(
+    library_dir: &Path,
+    platform: &str,
+    backend: Option<PlatformBackend>,
+    config: &mut Config,
+) -> Result<()> {
     let platform_lowercase = platform.to_lowercase();
 
     if is_platform_in_config(&platform_lowercase, config) {
-        log_info_to_console(&format!(
+        return Err(Error::Invalid(format!(
             "[add-platform] {} is already in the config.",
             &platform
-        ));
-        exit(1);
+        )));
+    }
+    if let Some(backend) = backend {
+        config
+            .platform_backends
+            .insert(platform_lowercase.clone(), backend);
     }
     config.platforms.push(platform_lowercase);
-    save_config_to_file(config);
+    save_config_to_file(library_dir, config)?;
 
     log_success_to_console(&format!(
         "[add-platform] Added {} to the config.",
         &platform
     ));
+
+    Ok(())
 }
 
 /// Indicates whether a platform was added to the config or not.
@@ -182,37 +422,45 @@ pub fn add_platform_to_config(platform: &str, config: &mut Config) {
 /// `platform` - The platform to check if exists or not.
 /// `config` - The configuration file.
 pub fn is_platform_in_config(platform: &str, config: &mut Config) -> bool {
-    return config.platforms.iter().any(|i| i == platform);
+    config.platforms.iter().any(|i| i == platform)
 }
 
 /// Removes a platform from the config file if it exists.
 ///
 /// # Arguments
 ///
+/// `library_dir` - The path to the library's directory.
 /// `platform` - The platform to remove from the config file.
 /// `config` - The configuration file.
-pub fn remove_platform_from_config_if_exists(platform: &str, config: &mut Config) {
+pub fn remove_platform_from_config_if_exists(
+    library_dir: &Path,
+    platform: &str,
+    config: &mut Config,
+) -> Result<()> {
     let platform_lowercase = platform.to_lowercase();
 
-    if !is_platform_in_config(&platform_lowercase, config) {
-        log_info_to_console(&format!(
-            "[remove-platform] {} can't be removed because doesn't exist in the config.",
-            &platform
-        ));
-        exit(1);
-    }
-
-    let index = config
+    let index = match config
         .platforms
         .iter()
         .position(|x| *x == platform_lowercase)
-        .unwrap();
+    {
+        Some(index) => index,
+        None => {
+            return Err(Error::Invalid(format!(
+                "[remove-platform] {} can't be removed because doesn't exist in the config.",
+                &platform
+            )))
+        }
+    };
     config.platforms.remove(index);
+    config.platform_backends.remove(&platform_lowercase);
 
-    save_config_to_file(config);
+    save_config_to_file(library_dir, config)?;
 
     log_success_to_console(&format!(
         "[remove-platform] {} removed from the config.",
         &platform
     ));
+
+    Ok(())
 }
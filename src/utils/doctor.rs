@@ -0,0 +1,346 @@
+use std::fmt;
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use convert_case::{Case, Casing};
+use walkdir::WalkDir;
+
+use crate::android_utils::get_android_ndk_dir;
+use crate::cargo_config::CargoConfig;
+use crate::config_utils::{
+    add_module_to_config, get_gdnlib_path, get_module_file_path,
+    remove_module_from_config_if_exists, Api, Config,
+};
+use crate::emscripten_utils::find_emcc;
+use crate::error::{Context, Result};
+use crate::lib_utils::{get_registered_classes, remove_module_from_lib};
+use crate::platforms::{get_platform, BuildBackend};
+
+/// A problem found while checking that the library and the Godot project
+/// still agree with each other.
+#[derive(Debug)]
+pub enum Problem {
+    /// There's no project.godot file at the Godot project path.
+    MissingGodotProject(PathBuf),
+    /// A module in the config doesn't have a file in the library's src
+    /// directory.
+    MissingModuleFile(String),
+    /// A class is registered in the init function but isn't in the config.
+    ModuleNotInConfig(String),
+    /// A gdns file points to a gdnlib file that doesn't exist. The module is
+    /// set if the gdns file is for one of the library's modules.
+    GdnsMissingGdnlib {
+        gdns_path: PathBuf,
+        gdnlib_path: String,
+        module_name: Option<String>,
+    },
+    /// A binary needed to create or build the library isn't installed.
+    MissingBinary(&'static str),
+    /// The library has Android platforms but the Android NDK can't be found.
+    MissingAndroidNdk,
+}
+
+impl Problem {
+    /// Indicates whether the problem can be fixed automatically or not.
+    pub fn is_fixable(&self) -> bool {
+        match self {
+            Problem::MissingModuleFile(_) | Problem::ModuleNotInConfig(_) => true,
+            Problem::GdnsMissingGdnlib { module_name, .. } => module_name.is_some(),
+            Problem::MissingGodotProject(_)
+            | Problem::MissingBinary(_)
+            | Problem::MissingAndroidNdk => false,
+        }
+    }
+
+    /// Fixes the problem if it can be fixed automatically.
+    ///
+    /// # Arguments
+    ///
+    /// `library_dir` - The path to the library's directory.
+    /// `config` - The configuration of the library.
+    pub fn fix(&self, library_dir: &Path, config: &mut Config) -> Result<()> {
+        match self {
+            Problem::MissingModuleFile(module_name) => {
+                // Without its file the module can't be compiled so we remove
+                // every trace of it from the config and the lib file.
+                remove_module_from_config_if_exists(library_dir, module_name, config)?;
+                remove_module_from_lib(library_dir, module_name)?;
+            }
+            Problem::ModuleNotInConfig(module_name) => {
+                add_module_to_config(library_dir, module_name, None, config)?;
+            }
+            Problem::GdnsMissingGdnlib {
+                gdns_path,
+                gdnlib_path,
+                module_name: Some(_),
+            } => {
+                let gdns_contents = read_to_string(gdns_path)
+                    .context("Unable to read gdns file while fixing it")?;
+                let fixed_gdns_contents = gdns_contents.replace(
+                    &format!("path=\"res://{}\"", gdnlib_path),
+                    &format!("path=\"res://{}.gdnlib\"", get_gdnlib_path(config)),
+                );
+                write(gdns_path, fixed_gdns_contents).context("Unable to fix gdns file")?;
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Problem {
+    /// Writes a description of the problem that can be shown to the user.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::MissingGodotProject(godot_project_path) => write!(
+                f,
+                "There's no project.godot file in {}",
+                godot_project_path.display()
+            ),
+            Problem::MissingModuleFile(module_name) => write!(
+                f,
+                "The module {} is in the config but its file doesn't exist",
+                module_name
+            ),
+            Problem::ModuleNotInConfig(module_name) => write!(
+                f,
+                "The class {} is registered in lib.rs but isn't in the config",
+                module_name
+            ),
+            Problem::GdnsMissingGdnlib {
+                gdns_path,
+                gdnlib_path,
+                ..
+            } => write!(
+                f,
+                "{} points to res://{} which doesn't exist",
+                gdns_path.display(),
+                gdnlib_path
+            ),
+            Problem::MissingBinary(binary) => {
+                write!(f, "{} isn't installed or isn't in the PATH", binary)
+            }
+            Problem::MissingAndroidNdk => write!(
+                f,
+                "The library has Android platforms but the Android NDK can't be found in ANDROID_NDK_HOME or ANDROID_SDK_ROOT"
+            ),
+        }
+    }
+}
+
+/// Returns all of the problems found with the library and the Godot project.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `config` - The configuration of the library.
+/// `cargo_config` - The cargo configuration of the library.
+pub fn find_problems(
+    library_dir: &Path,
+    config: &Config,
+    cargo_config: &CargoConfig,
+) -> Result<Vec<Problem>> {
+    let godot_project_path = PathBuf::from(&cargo_config.env.godot_project_path);
+
+    let mut problems: Vec<Problem> = vec![];
+
+    if !godot_project_path.join("project.godot").exists() {
+        problems.push(Problem::MissingGodotProject(godot_project_path.clone()));
+    }
+
+    problems.extend(find_missing_module_files(library_dir, config));
+    problems.extend(find_modules_not_in_config(library_dir, config)?);
+
+    // Only GDNative libraries have gdns files in the Godot project.
+    if config.api == Api::Gdnative {
+        problems.extend(find_gdns_files_missing_gdnlib(config, &godot_project_path));
+    }
+
+    problems.extend(find_missing_binaries(config));
+
+    let has_android_platforms = config.platforms.iter().any(|platform| {
+        get_platform(platform).is_some_and(|platform| {
+            matches!(
+                platform.get_build_backend(&config.platform_backends),
+                BuildBackend::AndroidNdk { .. }
+            )
+        })
+    });
+    if has_android_platforms && get_android_ndk_dir().is_err() {
+        problems.push(Problem::MissingAndroidNdk);
+    }
+
+    Ok(problems)
+}
+
+/// Returns a problem for every module in the config that doesn't have a file
+/// in the library's src directory.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `config` - The configuration of the library.
+fn find_missing_module_files(library_dir: &Path, config: &Config) -> Vec<Problem> {
+    config
+        .modules
+        .iter()
+        .filter(|module_name| {
+            !library_dir
+                .join(get_module_file_path(module_name, config))
+                .exists()
+        })
+        .map(|module_name| Problem::MissingModuleFile(module_name.to_owned()))
+        .collect()
+}
+
+/// Returns a problem for every class registered in the init function of the
+/// lib file that isn't in the config.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `config` - The configuration of the library.
+fn find_modules_not_in_config(library_dir: &Path, config: &Config) -> Result<Vec<Problem>> {
+    let library_name_snake_case = config.name.to_case(Case::Snake);
+
+    Ok(get_registered_classes(library_dir)?
+        .into_iter()
+        .filter(|class_name| {
+            // The root module of a plugin is never added to the config so it
+            // shouldn't be reported.
+            let is_plugin_module =
+                config.is_plugin && class_name.to_case(Case::Snake) == library_name_snake_case;
+
+            !is_plugin_module
+                && !config
+                    .modules
+                    .iter()
+                    .any(|module_name| module_name.to_case(Case::Pascal) == *class_name)
+        })
+        .map(Problem::ModuleNotInConfig)
+        .collect())
+}
+
+/// Returns a problem for every gdns file in the Godot project that points to
+/// a gdnlib file that doesn't exist.
+///
+/// # Arguments
+///
+/// `config` - The configuration of the library.
+/// `godot_project_path` - The path to the Godot project.
+fn find_gdns_files_missing_gdnlib(config: &Config, godot_project_path: &Path) -> Vec<Problem> {
+    let mut problems: Vec<Problem> = vec![];
+
+    for entry in WalkDir::new(godot_project_path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if entry.path().extension().and_then(|ext| ext.to_str()) != Some("gdns") {
+            continue;
+        }
+
+        let gdns_contents = match read_to_string(entry.path()) {
+            Ok(gdns_contents) => gdns_contents,
+            Err(_) => continue,
+        };
+
+        let gdnlib_path = match get_gdns_gdnlib_path(&gdns_contents) {
+            Some(gdnlib_path) => gdnlib_path,
+            None => continue,
+        };
+
+        if godot_project_path.join(&gdnlib_path).exists() {
+            continue;
+        }
+
+        // The gdns file can only be fixed if it's for one of the library's
+        // modules, otherwise it could belong to another library.
+        let module_name = get_gdns_resource_name(&gdns_contents).and_then(|resource_name| {
+            config
+                .modules
+                .iter()
+                .find(|module_name| module_name.to_case(Case::Pascal) == resource_name)
+                .cloned()
+        });
+
+        problems.push(Problem::GdnsMissingGdnlib {
+            gdns_path: entry.path().to_path_buf(),
+            gdnlib_path,
+            module_name,
+        });
+    }
+
+    problems
+}
+
+/// Returns a problem for every binary that is needed by the library but isn't
+/// installed. `cross` and `docker` are only needed if the library has
+/// platforms to build for that are built with cross, which includes HTML5
+/// when the Emscripten SDK isn't on the host, and `cargo-zigbuild` and `zig`
+/// are only needed if any are built with zigbuild.
+///
+/// # Arguments
+///
+/// `config` - The configuration of the library.
+fn find_missing_binaries(config: &Config) -> Vec<Problem> {
+    let mut binaries = vec!["rustfmt"];
+    let build_backends: Vec<BuildBackend> = config
+        .platforms
+        .iter()
+        .filter_map(|platform| get_platform(platform))
+        .map(|platform| platform.get_build_backend(&config.platform_backends))
+        .collect();
+    let has_cross_platforms = build_backends
+        .iter()
+        .any(|build_backend| match build_backend {
+            BuildBackend::Cross => true,
+            BuildBackend::Emscripten => find_emcc().is_none(),
+            _ => false,
+        });
+    if has_cross_platforms {
+        binaries.push("cross");
+        binaries.push("docker");
+    }
+    if build_backends.contains(&BuildBackend::Zigbuild) {
+        binaries.push("cargo-zigbuild");
+        binaries.push("zig");
+    }
+
+    binaries
+        .into_iter()
+        .filter(|binary| Command::new(binary).arg("--version").output().is_err())
+        .map(Problem::MissingBinary)
+        .collect()
+}
+
+/// Returns the path, relative to the Godot project, of the gdnlib file that a
+/// gdns file points to.
+///
+/// # Arguments
+///
+/// `gdns_contents` - The contents of the gdns file.
+fn get_gdns_gdnlib_path(gdns_contents: &str) -> Option<String> {
+    let ext_resource_line = gdns_contents.lines().find(|line| {
+        line.starts_with("[ext_resource") && line.contains("type=\"GDNativeLibrary\"")
+    })?;
+
+    let path_start = ext_resource_line.find("path=\"res://")? + "path=\"res://".len();
+    let path_len = ext_resource_line[path_start..].find('"')?;
+
+    Some(ext_resource_line[path_start..path_start + path_len].to_string())
+}
+
+/// Returns the name of the resource that a gdns file describes.
+///
+/// # Arguments
+///
+/// `gdns_contents` - The contents of the gdns file.
+fn get_gdns_resource_name(gdns_contents: &str) -> Option<String> {
+    gdns_contents.lines().find_map(|line| {
+        line.strip_prefix("resource_name = \"")
+            .and_then(|value| value.strip_suffix('"'))
+            .map(|value| value.to_string())
+    })
+}
@@ -0,0 +1,81 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::platforms::Platform;
+
+/// The flags that make rustc link the library as an Emscripten side module,
+/// which is the only kind of library that Godot's HTML5 export can load.
+/// Unwinding needs Godot to be built with `-sWASM_BIGINT` so panics abort.
+const SIDE_MODULE_RUSTFLAGS: &[&str] = &[
+    "-Clink-arg=-sSIDE_MODULE=2",
+    "-Crelocation-model=pic",
+    "-Cpanic=abort",
+];
+
+/// Returns the path to `emcc` from the Emscripten SDK on the host, if there
+/// is one. The SDK at `EMSDK` is used if it's set, otherwise `emcc` has to be
+/// on the PATH.
+pub fn find_emcc() -> Option<PathBuf> {
+    let emcc_name = if cfg!(windows) { "emcc.bat" } else { "emcc" };
+
+    if let Some(emsdk_dir) = env::var_os("EMSDK").filter(|value| !value.is_empty()) {
+        let emcc_path = PathBuf::from(emsdk_dir)
+            .join("upstream")
+            .join("emscripten")
+            .join(emcc_name);
+        if emcc_path.exists() {
+            return Some(emcc_path);
+        }
+    }
+
+    Command::new(emcc_name)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .ok()
+        .filter(|status| status.success())
+        .map(|_| PathBuf::from(emcc_name))
+}
+
+/// Adds the arguments that build the library as a side module to a `cargo
+/// build` or `cross build` command. The standard library that rustup ships
+/// isn't built as position independent code so it's rebuilt along with the
+/// library, which needs the nightly toolchain that the command is run with.
+///
+/// # Arguments
+///
+/// `build_command` - The command that builds the library.
+/// `platform` - The platform that's being built.
+/// `emcc_path` - The path to `emcc` on the host, if the library isn't built with cross.
+pub fn add_emscripten_args(
+    build_command: &mut Command,
+    platform: &Platform,
+    emcc_path: Option<&PathBuf>,
+) {
+    build_command.arg("-Zbuild-std=core,std,alloc,panic_abort");
+
+    for rust_triple in platform.rust_triples {
+        // The flags are passed with `--config` so that they're added to any
+        // rustflags that the library already has for the target.
+        let rustflags = SIDE_MODULE_RUSTFLAGS
+            .iter()
+            .map(|flag| format!("\"{}\"", flag))
+            .collect::<Vec<String>>()
+            .join(", ");
+        build_command
+            .arg("--config")
+            .arg(format!("target.{}.rustflags=[{}]", rust_triple, rustflags));
+
+        if let Some(emcc_path) = emcc_path {
+            build_command.env(
+                format!(
+                    "CARGO_TARGET_{}_LINKER",
+                    rust_triple.to_uppercase().replace('-', "_")
+                ),
+                emcc_path,
+            );
+        }
+    }
+}
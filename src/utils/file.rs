@@ -1,5 +1,7 @@
 use std::{fs::write, io::Result, path::Path, process::Command};
 
+use crate::log_utils::{get_child_stdout, log_command_to_console};
+
 /// Writes Rust code to a file and then runs `rustfmt` to format it.
 ///
 /// # Arguments
@@ -9,7 +11,12 @@ use std::{fs::write, io::Result, path::Path, process::Command};
 pub fn write_and_fmt<P: AsRef<Path>, S: ToString>(path: P, code: S) -> Result<()> {
     write(&path, code.to_string())?;
 
-    Command::new("rustfmt").arg(path.as_ref()).spawn()?.wait()?;
+    let mut rustfmt_command = Command::new("rustfmt");
+    rustfmt_command
+        .arg(path.as_ref())
+        .stdout(get_child_stdout());
+    log_command_to_console(&rustfmt_command);
+    rustfmt_command.spawn()?.wait()?;
 
     Ok(())
 }
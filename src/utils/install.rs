@@ -0,0 +1,232 @@
+use std::fs::{create_dir_all, metadata, read_dir, remove_file, rename, File, OpenOptions};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Installs a built dynamic library into the Godot project. The dynamic
+/// library is first copied to a temporary file next to the destination and
+/// then renamed over it so that Godot never loads a half written library.
+/// Returns whether the dynamic library was copied, which it isn't if the
+/// installed one is already the same.
+///
+/// # Arguments
+///
+/// `from` - The path to the built dynamic library.
+/// `to` - The path to install the dynamic library to.
+pub fn install_dynamic_library(from: &Path, to: &Path) -> Result<bool> {
+    // A successful build should always produce the dynamic library so if it
+    // isn't there the build put it somewhere we don't expect.
+    if !from.is_file() {
+        return Err(Error::ArtifactMissing(from.to_path_buf()));
+    }
+
+    let copy_failed = |source: io::Error| Error::CopyFailed {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+        source,
+    };
+
+    // Rebuilds often don't change the dynamic library so we skip the copy
+    // when the installed one is already the same.
+    if to.is_file() && is_same_contents(from, to).map_err(copy_failed)? {
+        return Ok(false);
+    }
+
+    if let Some(to_dir) = to.parent() {
+        create_dir_all(to_dir).map_err(copy_failed)?;
+    }
+
+    let temp_path = get_temp_path(to);
+    let result = copy_and_sync(from, &temp_path).and_then(|_| rename(&temp_path, to));
+
+    // The temporary file is left behind if the copy or the rename failed so
+    // we clean it up before returning the error.
+    if let Err(source) = result {
+        let _ = remove_file(&temp_path);
+        return Err(copy_failed(source));
+    }
+
+    Ok(true)
+}
+
+/// Installs a built dynamic library into the Godot project under a versioned
+/// file name, like `libplatformer_modules.3.so`, so that a dynamic library
+/// that Godot has loaded is never overwritten. The newest versioned copy is
+/// reused if it's the same as the built dynamic library. Returns the path to
+/// the installed copy.
+///
+/// # Arguments
+///
+/// `from` - The path to the built dynamic library.
+/// `to` - The path that the dynamic library would be installed to without a version.
+pub fn install_versioned_dynamic_library(from: &Path, to: &Path) -> Result<PathBuf> {
+    if !from.is_file() {
+        return Err(Error::ArtifactMissing(from.to_path_buf()));
+    }
+
+    let next_version = match get_versioned_copies(to).pop() {
+        Some((version, versioned_path)) => {
+            let is_unchanged =
+                is_same_contents(from, &versioned_path).map_err(|source| Error::CopyFailed {
+                    from: from.to_path_buf(),
+                    to: versioned_path.clone(),
+                    source,
+                })?;
+            if is_unchanged {
+                return Ok(versioned_path);
+            }
+            version + 1
+        }
+        None => 1,
+    };
+
+    let versioned_path = get_versioned_path(to, next_version);
+    install_dynamic_library(from, &versioned_path)?;
+
+    Ok(versioned_path)
+}
+
+/// Removes the versioned copies of a dynamic library other than the one in
+/// use. Copies that can't be removed, like ones that are still loaded by
+/// Godot on Windows, are left to be removed after a later build. Returns the
+/// number of copies that were removed.
+///
+/// # Arguments
+///
+/// `to` - The path that the dynamic library would be installed to without a version.
+/// `in_use` - The path to the dynamic library that Godot should load.
+pub fn remove_old_versioned_dynamic_libraries(to: &Path, in_use: &Path) -> usize {
+    get_versioned_copies(to)
+        .into_iter()
+        .filter(|(_, versioned_path)| versioned_path != in_use)
+        .filter(|(_, versioned_path)| remove_file(versioned_path).is_ok())
+        .count()
+}
+
+/// Returns the versioned copies of a dynamic library that are installed,
+/// sorted from the oldest to the newest version.
+///
+/// # Arguments
+///
+/// `to` - The path that the dynamic library would be installed to without a version.
+fn get_versioned_copies(to: &Path) -> Vec<(u32, PathBuf)> {
+    let (to_dir, stem, ext) = match (to.parent(), to.file_stem(), to.extension()) {
+        (Some(to_dir), Some(stem), Some(ext)) => (
+            to_dir,
+            stem.to_string_lossy().into_owned(),
+            ext.to_string_lossy().into_owned(),
+        ),
+        _ => return vec![],
+    };
+
+    let entries = match read_dir(to_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut versioned_copies: Vec<(u32, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let version = file_name
+                .strip_prefix(&format!("{}.", stem))?
+                .strip_suffix(&format!(".{}", ext))?
+                .parse::<u32>()
+                .ok()?;
+            Some((version, entry.path()))
+        })
+        .collect();
+    versioned_copies.sort();
+
+    versioned_copies
+}
+
+/// Returns the path to a versioned copy of a dynamic library.
+///
+/// # Arguments
+///
+/// `to` - The path that the dynamic library would be installed to without a version.
+/// `version` - The version of the copy.
+fn get_versioned_path(to: &Path, version: u32) -> PathBuf {
+    let stem = to
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    match to.extension() {
+        Some(ext) => to.with_file_name(format!("{}.{}.{}", stem, version, ext.to_string_lossy())),
+        None => to.with_file_name(format!("{}.{}", stem, version)),
+    }
+}
+
+/// Returns the path to the temporary file that a dynamic library is copied
+/// to before it's renamed to its destination. The temporary file is in the
+/// same directory as the destination so that the rename is atomic.
+///
+/// # Arguments
+///
+/// `to` - The path that the dynamic library is being installed to.
+fn get_temp_path(to: &Path) -> PathBuf {
+    let file_name = to
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    to.with_file_name(format!(".{}.tmp", file_name))
+}
+
+/// Copies a file and flushes the copy to disk before returning so that it's
+/// complete by the time it's renamed into place. The copy is synced through
+/// the handle it was written with since syncing a read only handle fails on
+/// Windows.
+///
+/// # Arguments
+///
+/// `from` - The path to the file to copy.
+/// `to` - The path to copy the file to.
+fn copy_and_sync(from: &Path, to: &Path) -> io::Result<()> {
+    let mut from_file = File::open(from)?;
+    let mut to_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(to)?;
+
+    io::copy(&mut from_file, &mut to_file)?;
+    to_file.set_permissions(from_file.metadata()?.permissions())?;
+    to_file.sync_all()
+}
+
+/// Indicates whether two files have the same contents. Files with different
+/// sizes are different without having to read them, otherwise their bytes
+/// are compared.
+///
+/// # Arguments
+///
+/// `a` - The path to the first file.
+/// `b` - The path to the second file.
+fn is_same_contents(a: &Path, b: &Path) -> io::Result<bool> {
+    if metadata(a)?.len() != metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    let mut a_reader = BufReader::new(File::open(a)?);
+    let mut b_reader = BufReader::new(File::open(b)?);
+    loop {
+        let a_buf = a_reader.fill_buf()?;
+        let b_buf = b_reader.fill_buf()?;
+        if a_buf.is_empty() || b_buf.is_empty() {
+            return Ok(a_buf.is_empty() && b_buf.is_empty());
+        }
+
+        // The readers can fill their buffers with different amounts so only
+        // the bytes that both have are compared each time.
+        let len = a_buf.len().min(b_buf.len());
+        if a_buf[..len] != b_buf[..len] {
+            return Ok(false);
+        }
+        a_reader.consume(len);
+        b_reader.consume(len);
+    }
+}
@@ -1,116 +1,556 @@
-use regex::Regex;
-use std::env::current_dir;
+use std::cmp::Reverse;
 use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
 
 use convert_case::{Case, Casing};
+use proc_macro2::{LineColumn, Span};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Expr, FnArg, Item, ItemFn, Pat, Stmt, Type};
 
-use crate::config_utils::Config;
+use crate::config_utils::{Api, Config};
+use crate::error::{Context, Error, Result};
 use crate::file_utils::write_and_fmt;
 
+/// Returns the path to the lib.rs file.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+fn get_lib_file_path(library_dir: &Path) -> PathBuf {
+    library_dir.join("src").join("lib.rs")
+}
+
 /// Returns the contents of the lib.rs file as a string.
-pub fn get_lib_file_contents() -> String {
-    let current_dir = current_dir().expect("Unable to get current directory");
-    let lib_file_contents =
-        read_to_string(current_dir.join("src").join("lib.rs")).expect("Unable to read lib file");
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+pub fn get_lib_file_contents(library_dir: &Path) -> Result<String> {
+    read_to_string(get_lib_file_path(library_dir)).context("Unable to read lib file")
+}
 
-    return lib_file_contents;
+/// Parses the contents of the lib.rs file into its syntax tree. The syntax
+/// tree is only used to find where things are in the file, the edits
+/// themselves are made to the original contents so that any comments and
+/// formatting the user has in the lib file are kept.
+///
+/// # Arguments
+///
+/// `lib_contents` - The contents of the lib file.
+fn parse_lib_file(lib_contents: &str) -> Result<syn::File> {
+    syn::parse_file(lib_contents).context("Unable to parse the lib.rs file")
 }
 
-/// Takes a regex pattern to find matching lines and then another regex pattern
-/// to find the module name within that match to find where the next module
-/// definition can be placed.
+/// Returns the byte offset in the lib file of a line and column from a span.
 ///
 /// # Arguments
 ///
-/// `line_pattern` - The regex pattern to use to look for the line before the insert location.
-/// `is_first` - Indicates whether this is the first module being added.
-/// `config` - The config to use.
 /// `lib_contents` - The contents of the lib file.
-pub fn get_insert_location(
-    line_pattern: &str,
-    is_first: bool,
-    config: &Config,
-    lib_contents: &String,
-) -> (usize, Vec<String>) {
-    // Since we want to add our new module after all of the currently existing
-    // ones, we need to keep updating the insert position to the end position of
-    // the last module found.
-    let mut insert_pos = 0;
-
-    // Create a copy of the modules hashmap so that we don't have to modify the
-    // original one.
-    let modules_copy = config.modules.clone();
-
-    // Create the regex pattern used to check for the lines that might contain
-    // our module definitions.
-    let line_regex = Regex::new(line_pattern).expect("Unable to create regex");
-
-    for line_match in line_regex.find_iter(&lib_contents) {
-        if is_first {
-            // If this is the first module to be added, meaning there's only 1
-            // entry in the modules hashmap, then we don't have to search for
-            // other modules and can just write it within the init function.
-            return (line_match.end(), modules_copy);
+/// `location` - The line and column to get the byte offset of.
+fn get_offset(lib_contents: &str, location: LineColumn) -> usize {
+    // Lines are 1-indexed and columns are counted in characters so we have to
+    // walk the lines to get to the byte offset.
+    let line_offset: usize = lib_contents
+        .split_inclusive('\n')
+        .take(location.line - 1)
+        .map(|line| line.len())
+        .sum();
+    let column_offset: usize = lib_contents[line_offset..]
+        .chars()
+        .take(location.column)
+        .map(|c| c.len_utf8())
+        .sum();
+
+    line_offset + column_offset
+}
+
+/// Returns the start and end byte offsets in the lib file of a span.
+///
+/// # Arguments
+///
+/// `lib_contents` - The contents of the lib file.
+/// `span` - The span to get the byte offsets of.
+fn get_span_range(lib_contents: &str, span: Span) -> (usize, usize) {
+    (
+        get_offset(lib_contents, span.start()),
+        get_offset(lib_contents, span.end()),
+    )
+}
+
+/// Returns the range to remove from the lib file so that removing the span
+/// doesn't leave behind an empty line if the span was on a line of its own.
+///
+/// # Arguments
+///
+/// `lib_contents` - The contents of the lib file.
+/// `span` - The span of the code to remove.
+fn get_removal_range(lib_contents: &str, span: Span) -> (usize, usize) {
+    let (start, end) = get_span_range(lib_contents, span);
+
+    let line_start = lib_contents[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = lib_contents[end..]
+        .find('\n')
+        .map_or(lib_contents.len(), |i| end + i + 1);
+
+    let only_whitespace_before = lib_contents[line_start..start].trim().is_empty();
+    let only_whitespace_after = lib_contents[end..line_end].trim().is_empty();
+
+    if only_whitespace_before && only_whitespace_after {
+        (line_start, line_end)
+    } else {
+        (start, end)
+    }
+}
+
+/// Returns the name of the function that is used to initialize the library.
+/// This is the function passed to the `godot_init!` macro which is `init` by
+/// default.
+///
+/// # Arguments
+///
+/// `lib_file` - The parsed lib file.
+fn get_init_fn_name(lib_file: &syn::File) -> String {
+    lib_file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Macro(item_macro) if item_macro.mac.path.is_ident("godot_init") => item_macro
+                .mac
+                .parse_body::<syn::Ident>()
+                .ok()
+                .map(|ident| ident.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| "init".to_string())
+}
+
+/// Returns the function that is used to initialize the library.
+///
+/// # Arguments
+///
+/// `lib_file` - The parsed lib file.
+fn get_init_fn(lib_file: &syn::File) -> Option<&ItemFn> {
+    let init_fn_name = get_init_fn_name(lib_file);
+
+    lib_file.items.iter().find_map(|item| match item {
+        Item::Fn(item_fn) if item_fn.sig.ident == init_fn_name => Some(item_fn),
+        _ => None,
+    })
+}
+
+/// Returns the name of the `InitHandle` argument of the init function.
+///
+/// # Arguments
+///
+/// `init_fn` - The init function.
+fn get_init_handle_name(init_fn: &ItemFn) -> String {
+    init_fn
+        .sig
+        .inputs
+        .iter()
+        .find_map(|input| match input {
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(pat_ident) => Some(pat_ident.ident.to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_else(|| "handle".to_string())
+}
+
+/// Returns the `mod` items in the lib file for a module. This matches any
+/// `mod` item with the module's name no matter its visibility or whether it
+/// is declared inline or not.
+///
+/// # Arguments
+///
+/// `lib_file` - The parsed lib file.
+/// `module_name_snake_case` - The snake_case name of the module.
+fn get_module_mod_items<'a>(
+    lib_file: &'a syn::File,
+    module_name_snake_case: &str,
+) -> Vec<&'a syn::ItemMod> {
+    lib_file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Mod(item_mod) if item_mod.ident == module_name_snake_case => Some(item_mod),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A call to `add_class` or `add_tool_class` found in the init function.
+struct Registration {
+    /// The segments of the path of the class that is registered.
+    class_path: Vec<String>,
+    /// The span of the statement that registers the class.
+    span: Span,
+    /// Indicates whether the statement is directly in the init function or
+    /// nested in another block like a loop.
+    is_top_level: bool,
+}
+
+impl Registration {
+    /// Indicates whether the registration is for the provided module. The
+    /// class can be registered with its module path, like `player::Player`, or
+    /// with just its name if it was imported.
+    ///
+    /// # Arguments
+    ///
+    /// `module_name_snake_case` - The snake_case name of the module.
+    /// `module_name_pascal_case` - The PascalCase name of the module.
+    fn is_for_module(&self, module_name_snake_case: &str, module_name_pascal_case: &str) -> bool {
+        match self.class_path.as_slice() {
+            [.., module, class] => {
+                module == module_name_snake_case && class == module_name_pascal_case
+            }
+            [class] => class == module_name_pascal_case,
+            _ => false,
         }
-        insert_pos = line_match.end();
     }
+}
 
-    (insert_pos, modules_copy)
+/// Visits the statements of the init function and collects the class
+/// registrations in it.
+struct RegistrationVisitor {
+    /// The registrations found.
+    registrations: Vec<Registration>,
+    /// How many blocks deep the visitor currently is in the init function.
+    depth: usize,
 }
 
-/// Adds a module to the lib.rs file.
+impl RegistrationVisitor {
+    /// Returns the path of the class registered if the expression is a call
+    /// to `add_class` or `add_tool_class`.
+    ///
+    /// # Arguments
+    ///
+    /// `expr` - The expression to check.
+    fn get_registered_class_path(expr: &Expr) -> Option<Vec<String>> {
+        let method_call = match expr {
+            Expr::MethodCall(method_call) => method_call,
+            _ => return None,
+        };
+
+        if method_call.method != "add_class" && method_call.method != "add_tool_class" {
+            return None;
+        }
+
+        let turbofish = method_call.turbofish.as_ref()?;
+        match turbofish.args.first()? {
+            syn::GenericMethodArgument::Type(Type::Path(type_path)) => Some(
+                type_path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for RegistrationVisitor {
+    fn visit_block(&mut self, block: &'ast syn::Block) {
+        self.depth += 1;
+        visit::visit_block(self, block);
+        self.depth -= 1;
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        let expr = match stmt {
+            Stmt::Expr(expr) | Stmt::Semi(expr, _) => Some(expr),
+            _ => None,
+        };
+
+        match expr.and_then(RegistrationVisitor::get_registered_class_path) {
+            Some(class_path) => self.registrations.push(Registration {
+                class_path,
+                span: stmt.span(),
+                is_top_level: self.depth == 1,
+            }),
+            None => visit::visit_stmt(self, stmt),
+        }
+    }
+}
+
+/// Returns the class registrations in the init function.
+///
+/// # Arguments
+///
+/// `init_fn` - The init function.
+fn get_registrations(init_fn: &ItemFn) -> Vec<Registration> {
+    let mut visitor = RegistrationVisitor {
+        registrations: vec![],
+        depth: 0,
+    };
+    visitor.visit_block(&init_fn.block);
+
+    visitor.registrations
+}
+
+/// Adds a module to the lib.rs file. The `mod` item for the module is added
+/// after the last `mod` item and the class is registered after the last class
+/// registered in the init function. If either of these already exist in the
+/// lib file then they are left alone.
 ///
 /// # Arguments
-/// 
+///
+/// `library_dir` - The path to the library's directory.
 /// `module_name` - The name of the module to add.
 /// `config` - The config to use.
-pub fn add_module_to_lib(module_name: &str, config: &Config) {
-    let mut lib_file_contents = get_lib_file_contents();
-
-    // The position of where we should insert the `mod` statement for the
-    // module.
-    let module_mod_insert_location =
-        get_insert_location("mod.*;", false, &config, &lib_file_contents);
+pub fn add_module_to_lib(library_dir: &Path, module_name: &str, config: &Config) -> Result<()> {
+    let lib_file_contents = get_lib_file_contents(library_dir)?;
+    let lib_file = parse_lib_file(&lib_file_contents)?;
 
-    // Insert the new module's mod line after the last module's mod line.
     let module_name_snake_case = &module_name.to_case(Case::Snake);
-    let mod_line = format!("mod {};", module_name_snake_case);
-    lib_file_contents.insert_str(module_mod_insert_location.0, &mod_line);
+    let module_name_pascal_case = &module_name.to_case(Case::Pascal);
 
-    // Next we do the same thing for the handle turbofish statement for the
-    // module. However, this is different than the mod statement because if this
-    // is the first module created, then we need to look for the init function.
-    // If this is not the first module then we look for an existing module's
-    // turbofish statement.
+    // The edits to make to the lib file as pairs of the offset to insert at
+    // and the text to insert.
+    let mut insertions: Vec<(usize, String)> = vec![];
 
-    let handle_insert_location_first =
-        get_insert_location("init.*\\{", true, &config, &lib_file_contents);
+    if get_module_mod_items(&lib_file, module_name_snake_case).is_empty() {
+        // Insert the new module's mod item after the last mod item or at the
+        // start of the first item if there are no modules yet.
+        let last_mod_item = lib_file
+            .items
+            .iter()
+            .rev()
+            .find(|item| matches!(item, Item::Mod(_)));
+        let mod_line = format!("mod {};", module_name_snake_case);
 
-    let handle_insert_location_normal =
-        get_insert_location("handle.*;", true, &config, &lib_file_contents);
+        match (last_mod_item, lib_file.items.first()) {
+            (Some(item), _) => {
+                let (_, end) = get_span_range(&lib_file_contents, item.span());
+                insertions.push((end, format!("\n{}", mod_line)));
+            }
+            (None, Some(item)) => {
+                let (start, _) = get_span_range(&lib_file_contents, item.span());
+                insertions.push((start, format!("{}\n", mod_line)));
+            }
+            (None, None) => insertions.push((lib_file_contents.len(), mod_line)),
+        }
+    }
 
-    // Insert the module or plugin turbofish at the start of the init function or
-    // after the last module's turbofish.
+    // GDExtension classes register themselves so there's no handle statement
+    // to add for them.
+    if config.api == Api::Gdnative {
+        let init_fn = get_init_fn(&lib_file).ok_or_else(|| {
+            Error::Invalid("Unable to find the init function in lib.rs".to_string())
+        })?;
+        let registrations = get_registrations(init_fn);
+
+        let is_registered = registrations
+            .iter()
+            .any(|r| r.is_for_module(module_name_snake_case, module_name_pascal_case));
+
+        if !is_registered {
+            let handle_name = get_init_handle_name(init_fn);
+            let add_class_method = if config.is_plugin {
+                "add_tool_class"
+            } else {
+                "add_class"
+            };
+            let handle_line = format!(
+                "{}.{}::<{}::{}>();",
+                handle_name, add_class_method, module_name_snake_case, module_name_pascal_case
+            );
+
+            // Insert the registration after the last registration in the init
+            // function or at the start of the init function if there are none.
+            match registrations.iter().rev().find(|r| r.is_top_level) {
+                Some(registration) => {
+                    let (_, end) = get_span_range(&lib_file_contents, registration.span);
+                    insertions.push((end, format!("\n{}", handle_line)));
+                }
+                None => {
+                    let (start, _) =
+                        get_span_range(&lib_file_contents, init_fn.block.brace_token.span);
+                    insertions.push((start + 1, format!("\n{}", handle_line)));
+                }
+            }
+        }
+    }
+
+    write_lib_file_with_edits(
+        library_dir,
+        lib_file_contents,
+        insertions
+            .into_iter()
+            .map(|(offset, text)| (offset, offset, text))
+            .collect(),
+    )
+}
+
+/// Removes a module from the lib.rs file. This removes every `mod` item for
+/// the module and every place the module's class is registered in the init
+/// function.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `module_name` - The name of the module to remove.
+pub fn remove_module_from_lib(library_dir: &Path, module_name: &str) -> Result<()> {
+    let lib_file_contents = get_lib_file_contents(library_dir)?;
+    let lib_file = parse_lib_file(&lib_file_contents)?;
+
+    let module_name_snake_case = &module_name.to_case(Case::Snake);
     let module_name_pascal_case = &module_name.to_case(Case::Pascal);
-    let handle_line = if config.is_plugin {
-        format!(
-            "handle.add_tool_class::<{}::{}>();",
-            &module_name_snake_case, module_name_pascal_case
-        )
-    } else {
-        format!(
-            "handle.add_class::<{}::{}>();",
-            &module_name_snake_case, module_name_pascal_case
-        )
-    };
 
-    let handle_insert_location_to_use = if config.modules.len() == 0 {
-        handle_insert_location_first
-    } else {
-        handle_insert_location_normal
+    let mut removals: Vec<(usize, usize, String)> =
+        get_module_mod_items(&lib_file, module_name_snake_case)
+            .into_iter()
+            .map(|item_mod| {
+                let (start, end) = get_removal_range(&lib_file_contents, item_mod.span());
+                (start, end, String::new())
+            })
+            .collect();
+
+    if let Some(init_fn) = get_init_fn(&lib_file) {
+        for registration in get_registrations(init_fn) {
+            if registration.is_for_module(module_name_snake_case, module_name_pascal_case) {
+                let (start, end) = get_removal_range(&lib_file_contents, registration.span);
+                removals.push((start, end, String::new()));
+            }
+        }
+    }
+
+    write_lib_file_with_edits(library_dir, lib_file_contents, removals)
+}
+
+/// Returns the names of the classes registered in the init function of the
+/// lib.rs file. GDExtension libraries don't have an init function so no
+/// classes are returned for them.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+pub fn get_registered_classes(library_dir: &Path) -> Result<Vec<String>> {
+    let lib_file_contents = get_lib_file_contents(library_dir)?;
+    let lib_file = parse_lib_file(&lib_file_contents)?;
+
+    Ok(match get_init_fn(&lib_file) {
+        Some(init_fn) => get_registrations(init_fn)
+            .into_iter()
+            .filter_map(|registration| registration.class_path.last().cloned())
+            .collect(),
+        None => vec![],
+    })
+}
+
+/// Applies edits to the contents of the lib file and writes and formats it.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `lib_file_contents` - The contents of the lib file.
+/// `edits` - The start and end offsets of the text to replace and the text to replace it with.
+fn write_lib_file_with_edits(
+    library_dir: &Path,
+    lib_file_contents: String,
+    edits: Vec<(usize, usize, String)>,
+) -> Result<()> {
+    write_file_with_edits(&get_lib_file_path(library_dir), lib_file_contents, edits)
+}
+
+/// Applies edits to the contents of a Rust file and writes and formats it.
+///
+/// # Arguments
+///
+/// `path` - The path to write the file to.
+/// `contents` - The contents of the file.
+/// `edits` - The start and end offsets of the text to replace and the text to replace it with.
+fn write_file_with_edits(
+    path: &Path,
+    mut contents: String,
+    mut edits: Vec<(usize, usize, String)>,
+) -> Result<()> {
+    // The edits are applied from the end of the file to the start so that
+    // applying an edit doesn't change the offsets of the ones before it.
+    edits.sort_by_key(|edit| Reverse(edit.0));
+    for (start, end, text) in edits {
+        contents.replace_range(start..end, &text);
+    }
+
+    write_and_fmt(path, contents).context("Unable to save or format file")
+}
+
+/// Visits every identifier in a file and collects the ones that should be
+/// renamed.
+struct IdentRenameVisitor<'a> {
+    /// The pairs of identifiers to rename and what to rename them to.
+    renames: &'a [(String, String)],
+    /// The spans of the identifiers to rename and their new names.
+    edits: Vec<(Span, String)>,
+}
+
+impl<'a, 'ast> Visit<'ast> for IdentRenameVisitor<'a> {
+    fn visit_ident(&mut self, ident: &'ast syn::Ident) {
+        if let Some((_, new_name)) = self.renames.iter().find(|(old_name, _)| ident == old_name) {
+            self.edits.push((ident.span(), new_name.to_owned()));
+        }
+    }
+}
+
+/// Renames identifiers in a Rust file. Only identifiers in the code itself
+/// are renamed so comments, strings, and the contents of macros like
+/// `godot_print!` are left alone.
+///
+/// # Arguments
+///
+/// `path` - The path to the Rust file.
+/// `renames` - The pairs of identifiers to rename and what to rename them to.
+pub fn rename_idents_in_file(path: &Path, renames: &[(String, String)]) -> Result<()> {
+    let contents = read_to_string(path).context("Unable to read file to rename identifiers in")?;
+    let file =
+        syn::parse_file(&contents).context("Unable to parse file to rename identifiers in")?;
+
+    let mut visitor = IdentRenameVisitor {
+        renames,
+        edits: vec![],
     };
+    visitor.visit_file(&file);
+
+    let edits = visitor
+        .edits
+        .into_iter()
+        .map(|(span, new_name)| {
+            let (start, end) = get_span_range(&contents, span);
+            (start, end, new_name)
+        })
+        .collect();
 
-    lib_file_contents.insert_str(handle_insert_location_to_use.0, &handle_line);
+    write_file_with_edits(path, contents, edits)
+}
 
-    write_and_fmt("src/lib.rs", lib_file_contents).expect("Unable to save or format lib");
+/// Renames a module in the lib.rs file. This renames its `mod` items, any
+/// `use` items for it, and every place its class is registered.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `old_module_name` - The current name of the module.
+/// `new_module_name` - The name to rename the module to.
+pub fn rename_module_in_lib(
+    library_dir: &Path,
+    old_module_name: &str,
+    new_module_name: &str,
+) -> Result<()> {
+    rename_idents_in_file(
+        &get_lib_file_path(library_dir),
+        &[
+            (
+                old_module_name.to_case(Case::Snake),
+                new_module_name.to_case(Case::Snake),
+            ),
+            (
+                old_module_name.to_case(Case::Pascal),
+                new_module_name.to_case(Case::Pascal),
+            ),
+        ],
+    )
 }
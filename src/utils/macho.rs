@@ -0,0 +1,138 @@
+use std::convert::TryInto;
+use std::fs::{read, write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Context, Error, Result};
+
+/// The magic number of a universal binary, which is always big-endian.
+const FAT_MAGIC: u32 = 0xcafe_babe;
+/// The magic number of a 32-bit Mach-O binary in the byte order of its
+/// architecture.
+const MH_MAGIC: u32 = 0xfeed_face;
+/// The magic number of a 64-bit Mach-O binary in the byte order of its
+/// architecture.
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+/// The size of the header of a universal binary.
+const FAT_HEADER_SIZE: usize = 8;
+/// The size of each architecture entry after the header of a universal
+/// binary.
+const FAT_ARCH_SIZE: usize = 20;
+/// The cpu type of arm64.
+const CPU_TYPE_ARM64: i32 = 0x0100_000c;
+
+/// A thin Mach-O binary that's merged into a universal binary.
+struct ThinBinary {
+    /// The cpu type from the binary's header.
+    cpu_type: i32,
+    /// The cpu subtype, along with its capabilities, from the binary's
+    /// header.
+    cpu_subtype: i32,
+    /// The power of two that the binary is aligned to in the universal
+    /// binary.
+    align: u32,
+    /// The contents of the binary.
+    contents: Vec<u8>,
+}
+
+/// Merges thin Mach-O binaries, like the dynamic libraries built for
+/// `x86_64-apple-darwin` and `aarch64-apple-darwin`, into one universal
+/// binary the same way that `lipo -create` does so that no macOS host is
+/// needed to create it.
+///
+/// # Arguments
+///
+/// `input_paths` - The paths to the thin binaries to merge, one for each architecture.
+/// `output_path` - The path to write the universal binary to.
+pub fn create_universal_binary(input_paths: &[PathBuf], output_path: &Path) -> Result<()> {
+    let mut thin_binaries: Vec<ThinBinary> = vec![];
+    for input_path in input_paths {
+        let thin_binary = read_thin_binary(input_path)?;
+        if thin_binaries
+            .iter()
+            .any(|merged| merged.cpu_type == thin_binary.cpu_type)
+        {
+            return Err(Error::Invalid(format!(
+                "{} has the same architecture as another binary being merged into {}",
+                input_path.display(),
+                output_path.display()
+            )));
+        }
+        thin_binaries.push(thin_binary);
+    }
+
+    // The binaries are placed after the header and the architecture entries,
+    // each at an offset aligned to its alignment.
+    let mut universal_binary: Vec<u8> = vec![];
+    universal_binary.extend_from_slice(&FAT_MAGIC.to_be_bytes());
+    universal_binary.extend_from_slice(&(thin_binaries.len() as u32).to_be_bytes());
+
+    let mut offset = FAT_HEADER_SIZE + FAT_ARCH_SIZE * thin_binaries.len();
+    let mut offsets: Vec<usize> = vec![];
+    for thin_binary in &thin_binaries {
+        let alignment = 1 << thin_binary.align;
+        offset = offset.next_multiple_of(alignment);
+        offsets.push(offset);
+
+        universal_binary.extend_from_slice(&thin_binary.cpu_type.to_be_bytes());
+        universal_binary.extend_from_slice(&thin_binary.cpu_subtype.to_be_bytes());
+        universal_binary.extend_from_slice(&(offset as u32).to_be_bytes());
+        universal_binary.extend_from_slice(&(thin_binary.contents.len() as u32).to_be_bytes());
+        universal_binary.extend_from_slice(&thin_binary.align.to_be_bytes());
+
+        offset += thin_binary.contents.len();
+    }
+
+    for (thin_binary, offset) in thin_binaries.iter().zip(offsets) {
+        universal_binary.resize(offset, 0);
+        universal_binary.extend_from_slice(&thin_binary.contents);
+    }
+
+    write(output_path, universal_binary).context(format!(
+        "Unable to write the universal binary to {}",
+        output_path.display()
+    ))
+}
+
+/// Reads a thin Mach-O binary along with the architecture from its header.
+///
+/// # Arguments
+///
+/// `path` - The path to the binary.
+fn read_thin_binary(path: &Path) -> Result<ThinBinary> {
+    let contents = read(path).context(format!("Unable to read {}", path.display()))?;
+    let not_thin_binary = || {
+        Error::Invalid(format!(
+            "{} isn't a thin Mach-O binary so it can't be merged into a universal binary",
+            path.display()
+        ))
+    };
+
+    let read_u32 = |index: usize, is_big_endian: bool| -> Option<u32> {
+        let bytes: [u8; 4] = contents.get(index..index + 4)?.try_into().ok()?;
+        Some(if is_big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    // The magic number tells whether the header is in little-endian or
+    // big-endian byte order.
+    let is_big_endian = match read_u32(0, false).ok_or_else(not_thin_binary)? {
+        MH_MAGIC | MH_MAGIC_64 => false,
+        magic if magic.swap_bytes() == MH_MAGIC || magic.swap_bytes() == MH_MAGIC_64 => true,
+        _ => return Err(not_thin_binary()),
+    };
+    let cpu_type = read_u32(4, is_big_endian).ok_or_else(not_thin_binary)? as i32;
+    let cpu_subtype = read_u32(8, is_big_endian).ok_or_else(not_thin_binary)? as i32;
+
+    // arm64 binaries use 16KB pages while everything else uses 4KB pages.
+    let align = if cpu_type == CPU_TYPE_ARM64 { 14 } else { 12 };
+
+    Ok(ThinBinary {
+        cpu_type,
+        cpu_subtype,
+        align,
+        contents,
+    })
+}
@@ -1,43 +1,47 @@
 use dunce::canonicalize;
 use std::{
-    env::current_dir,
-    io::Result,
+    io::{Error, ErrorKind, Result},
     path::{Path, PathBuf},
-    process::exit,
 };
 
-use crate::{config_utils::get_path_to_config_file, log_utils::log_error_to_console};
-
-/// Exits if the current path is not the path to the library's directory.
-pub fn exit_if_not_lib_dir() {
-    if !get_path_to_config_file().exists() {
-        log_error_to_console("This command must be used from the library directory");
-        exit(1);
+/// Returns the absolute path from a PathBuf. The path doesn't have to exist
+/// yet but its parent directory does.
+///
+/// # Arguments
+///
+/// `base_dir` - The directory that a relative path is relative to.
+/// `buf` - The PathBuf to return the absolute path of.
+pub fn get_absolute_path(base_dir: &Path, buf: &Path) -> Result<PathBuf> {
+    if !buf.is_absolute() {
+        absolute_path(base_dir, buf)
+    } else {
+        Ok(buf.to_path_buf())
     }
 }
 
-/// Returns the absolute path from a PathBuf.
+/// Returns the path of a file in the Godot project relative to the root of
+/// the Godot project, with forward slashes like Godot's `res://` paths use.
 ///
 /// # Arguments
 ///
-/// `buf` - The PathBuf to return the absolute path of.
-pub fn get_absolute_path(buf: &PathBuf) -> PathBuf {
-    return if !buf.is_absolute() {
-        absolute_path(buf)
-            .expect("Unable to create absolute path")
-            .as_path()
-            .to_owned()
-    } else {
-        Path::new(&buf).to_path_buf()
-    };
+/// `godot_project_path` - The path to the Godot project.
+/// `path` - The path to the file in the Godot project.
+pub fn get_godot_res_path(godot_project_path: &Path, path: &Path) -> String {
+    path.strip_prefix(godot_project_path)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 /// Returns the absolute path from a relative path.
 ///
 /// # Arguments
 ///
+/// `base_dir` - The directory that the path is relative to.
 /// `path` - The relative path to get the absolute path of.
-fn absolute_path<P>(path: P) -> Result<PathBuf>
+fn absolute_path<P>(base_dir: &Path, path: P) -> Result<PathBuf>
 where
     P: AsRef<Path>,
 {
@@ -45,22 +49,28 @@ where
     let mut absolute_path = if path.is_absolute() {
         path.to_path_buf()
     } else {
-        current_dir()?.join(path)
+        base_dir.join(path)
     };
 
+    // A path that doesn't exist yet is made absolute through its parent,
+    // which has to exist.
     match canonicalize(&absolute_path) {
         Ok(v) => absolute_path = v,
         Err(_e) => {
-            let parent = &absolute_path
-                .parent()
-                .expect("Unable to get the absolute path's parent");
-            let basename = &absolute_path
-                .file_stem()
-                .expect("Unable to get the absolute path's basename");
-            let parent_canon =
-                canonicalize(parent).expect("Unable to canonicalize parent directory");
+            let (parent, basename) = match (absolute_path.parent(), absolute_path.file_name()) {
+                (Some(parent), Some(basename)) => (parent, basename),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "{} doesn't have a parent directory",
+                            absolute_path.display()
+                        ),
+                    ))
+                }
+            };
 
-            absolute_path = parent_canon.join(basename);
+            absolute_path = canonicalize(parent)?.join(basename);
         }
     }
 
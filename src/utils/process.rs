@@ -0,0 +1,148 @@
+use std::io;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long the processes in a group get to exit after being asked to before
+/// they're killed.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// The process groups of every build that's running so that they can all be
+/// killed if the cli is interrupted.
+static RUNNING_PROCESS_GROUPS: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// The process groups of a set of builds that can be cancelled. Each build
+/// is spawned in its own process group so that cancelling it also kills the
+/// processes that it started, like rustc, cross, and docker, which would
+/// otherwise keep running and hold the lock on the target directory.
+#[derive(Debug, Default)]
+pub struct ProcessGroups {
+    /// The ids of the process groups that are running, which are the ids of
+    /// the processes that lead them.
+    ids: Mutex<Vec<u32>>,
+    /// Indicates whether the process groups were killed, after which no more
+    /// processes can be spawned.
+    is_killed: AtomicBool,
+}
+
+impl ProcessGroups {
+    /// Spawns a command as the leader of a new process group. The process
+    /// group has to be released once the process exits.
+    ///
+    /// # Arguments
+    ///
+    /// `command` - The command to spawn.
+    pub fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        let mut ids = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+        if self.is_killed() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "The build was cancelled before it started",
+            ));
+        }
+
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(command, 0);
+
+        let child = command.spawn()?;
+        ids.push(child.id());
+        lock_running_process_groups().push(child.id());
+
+        Ok(child)
+    }
+
+    /// Stops tracking the process group of a process that exited.
+    ///
+    /// # Arguments
+    ///
+    /// `child` - The process that leads the process group.
+    pub fn release(&self, child: &Child) {
+        self.ids
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|id| *id != child.id());
+        lock_running_process_groups().retain(|id| *id != child.id());
+    }
+
+    /// Indicates whether the process groups were killed.
+    pub fn is_killed(&self) -> bool {
+        self.is_killed.load(Ordering::SeqCst)
+    }
+
+    /// Kills every process group that's running and stops any more from
+    /// being spawned. The processes are asked to exit first and the ones that
+    /// are still running once the grace period is over are killed. The
+    /// processes that lead the groups have to be waited on by whoever spawned
+    /// them for the grace period to end early.
+    pub fn kill_all(&self) {
+        let ids = {
+            let ids = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+            self.is_killed.store(true, Ordering::SeqCst);
+            ids.clone()
+        };
+        ids.iter().for_each(|id| signal_process_group(*id, false));
+
+        let started_at = Instant::now();
+        while started_at.elapsed() < KILL_GRACE_PERIOD {
+            if self
+                .ids
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .is_empty()
+            {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        // The processes that the leaders started can outlive them so the
+        // groups are killed even if every leader exited.
+        ids.iter().for_each(|id| signal_process_group(*id, true));
+    }
+}
+
+/// Kills the process group of every build that's running and exits when the
+/// cli is interrupted, since builds in their own process groups don't receive
+/// the interrupt from the terminal.
+pub fn kill_process_groups_on_interrupt() -> io::Result<()> {
+    ctrlc::set_handler(|| {
+        let ids = lock_running_process_groups().clone();
+        ids.iter().for_each(|id| signal_process_group(*id, true));
+        std::process::exit(130);
+    })
+    .map_err(|e| io::Error::other(e.to_string()))
+}
+
+/// Returns the process groups of every build that's running.
+fn lock_running_process_groups() -> std::sync::MutexGuard<'static, Vec<u32>> {
+    RUNNING_PROCESS_GROUPS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Sends a signal to every process in a process group. Windows doesn't have
+/// process groups that can be signalled so the process tree is killed
+/// instead.
+///
+/// # Arguments
+///
+/// `id` - The id of the process group.
+/// `force` - Indicates whether the processes should be killed instead of asked to exit.
+fn signal_process_group(id: u32, force: bool) {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("taskkill");
+        command.arg("/T").arg("/F").arg("/PID").arg(id.to_string());
+        command
+    } else {
+        let mut command = Command::new("kill");
+        command
+            .arg(if force { "-KILL" } else { "-TERM" })
+            .arg("--")
+            .arg(format!("-{}", id));
+        command
+    };
+
+    let _ = command.stdout(Stdio::null()).stderr(Stdio::null()).status();
+}
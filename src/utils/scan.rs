@@ -0,0 +1,156 @@
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenTree;
+use syn::punctuated::Punctuated;
+use syn::visit::{self, Visit};
+use syn::{Attribute, ItemStruct, Token};
+use walkdir::WalkDir;
+
+use crate::config_utils::Api;
+
+/// A Godot class found in the library's source files.
+#[derive(Debug)]
+pub struct ScannedClass {
+    /// The name of the class.
+    pub name: String,
+    /// The Godot class that the class inherits from, if it could be found.
+    pub base_class: Option<String>,
+    /// The path to the file that the class is in.
+    pub file_path: PathBuf,
+}
+
+/// Visits the structs in a file and collects the ones that are Godot classes.
+struct ClassVisitor<'a> {
+    /// The Godot API that the library is for.
+    api: Api,
+    /// The path to the file being visited.
+    file_path: &'a Path,
+    /// The classes found.
+    classes: Vec<ScannedClass>,
+}
+
+impl<'a> ClassVisitor<'a> {
+    /// Indicates whether a derive attribute derives the trait that makes a
+    /// struct a Godot class, `NativeClass` for GDNative and `GodotClass` for
+    /// GDExtension. The trait can be derived with its path, like
+    /// `gdnative::NativeClass`.
+    ///
+    /// # Arguments
+    ///
+    /// `attr` - The attribute to check.
+    fn is_class_derive(&self, attr: &Attribute) -> bool {
+        if !attr.path.is_ident("derive") {
+            return false;
+        }
+
+        let derive_name = match self.api {
+            Api::Gdnative => "NativeClass",
+            Api::Gdextension => "GodotClass",
+        };
+
+        attr.parse_args_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)
+            .map(|paths| {
+                paths.iter().any(|path| {
+                    path.segments
+                        .last()
+                        .is_some_and(|segment| segment.ident == derive_name)
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the Godot class that a struct inherits from. GDNative classes
+    /// use `#[inherit(Node2D)]` and GDExtension classes use
+    /// `#[class(base=Node2D)]`.
+    ///
+    /// # Arguments
+    ///
+    /// `attrs` - The attributes of the struct.
+    fn get_base_class(&self, attrs: &[Attribute]) -> Option<String> {
+        attrs.iter().find_map(|attr| match self.api {
+            Api::Gdnative if attr.path.is_ident("inherit") => attr
+                .parse_args::<syn::Path>()
+                .ok()
+                .and_then(|path| path.segments.last().map(|s| s.ident.to_string())),
+            Api::Gdextension if attr.path.is_ident("class") => {
+                let tokens: Vec<TokenTree> = match attr.tokens.clone().into_iter().next() {
+                    Some(TokenTree::Group(group)) => group.stream().into_iter().collect(),
+                    _ => return None,
+                };
+
+                tokens.windows(3).find_map(|window| match window {
+                    [TokenTree::Ident(key), TokenTree::Punct(punct), TokenTree::Ident(value)]
+                        if key == "base" && punct.as_char() == '=' =>
+                    {
+                        Some(value.to_string())
+                    }
+                    _ => None,
+                })
+            }
+            _ => None,
+        })
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for ClassVisitor<'a> {
+    fn visit_item_struct(&mut self, item_struct: &'ast ItemStruct) {
+        if item_struct
+            .attrs
+            .iter()
+            .any(|attr| self.is_class_derive(attr))
+        {
+            self.classes.push(ScannedClass {
+                name: item_struct.ident.to_string(),
+                base_class: self.get_base_class(&item_struct.attrs),
+                file_path: self.file_path.to_path_buf(),
+            });
+        }
+
+        visit::visit_item_struct(self, item_struct);
+    }
+}
+
+/// Returns the Godot classes in the Rust files in the library's src
+/// directory. Files that can't be read or parsed are skipped. The paths to
+/// the files the classes are in are relative to the library's directory.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `api` - The Godot API that the library is for.
+pub fn scan_library_classes(library_dir: &Path, api: Api) -> Vec<ScannedClass> {
+    let mut classes: Vec<ScannedClass> = vec![];
+
+    for entry in WalkDir::new(library_dir.join("src"))
+        .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        if entry.path().extension().and_then(|ext| ext.to_str()) != Some("rs") {
+            continue;
+        }
+
+        let file = match read_to_string(entry.path())
+            .ok()
+            .and_then(|contents| syn::parse_file(&contents).ok())
+        {
+            Some(file) => file,
+            None => continue,
+        };
+
+        let mut visitor = ClassVisitor {
+            api,
+            file_path: entry
+                .path()
+                .strip_prefix(library_dir)
+                .unwrap_or(entry.path()),
+            classes: vec![],
+        };
+        visitor.visit_file(&file);
+
+        classes.extend(visitor.classes);
+    }
+
+    classes
+}
@@ -0,0 +1,70 @@
+use std::env::var_os;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Context, Result};
+
+/// The values that the placeholders in a module template are replaced with.
+pub struct TemplateValues<'a> {
+    /// The PascalCase name of the module, used for `{{pascal}}`.
+    pub pascal: &'a str,
+    /// The snake_case name of the module, used for `{{snake}}`.
+    pub snake: &'a str,
+    /// The Godot class that the module inherits from, used for `{{base}}`.
+    pub base: &'a str,
+    /// The snake_case name of the library, used for `{{library}}`.
+    pub library: &'a str,
+}
+
+/// Returns the directories that module templates are looked for in, in the
+/// order that they're checked. Templates in the library's `templates`
+/// directory take priority over the user's global templates.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+pub fn get_templates_dirs(library_dir: &Path) -> Vec<PathBuf> {
+    let mut templates_dirs: Vec<PathBuf> = vec![library_dir.join("templates")];
+
+    // The global templates are in the user's config directory which defaults
+    // to `~/.config` if `XDG_CONFIG_HOME` isn't set.
+    let config_dir = match var_os("XDG_CONFIG_HOME") {
+        Some(xdg_config_home) => Some(PathBuf::from(xdg_config_home)),
+        None => var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+    };
+    if let Some(config_dir) = config_dir {
+        templates_dirs.push(config_dir.join("godot-rust-cli").join("templates"));
+    }
+
+    templates_dirs
+}
+
+/// Returns the contents of the template with the provided name if it exists
+/// in any of the templates directories.
+///
+/// # Arguments
+///
+/// `library_dir` - The path to the library's directory.
+/// `template_name` - The name of the template without the `.rs` extension.
+pub fn get_template(library_dir: &Path, template_name: &str) -> Result<Option<String>> {
+    get_templates_dirs(library_dir)
+        .into_iter()
+        .map(|templates_dir| templates_dir.join(format!("{}.rs", template_name)))
+        .find(|template_path| template_path.exists())
+        .map(|template_path| read_to_string(template_path).context("Unable to read template file"))
+        .transpose()
+}
+
+/// Replaces the placeholders in a template with their values.
+///
+/// # Arguments
+///
+/// `template` - The contents of the template.
+/// `values` - The values to replace the placeholders with.
+pub fn render_template(template: &str, values: &TemplateValues) -> String {
+    template
+        .replace("{{pascal}}", values.pascal)
+        .replace("{{snake}}", values.snake)
+        .replace("{{base}}", values.base)
+        .replace("{{library}}", values.library)
+}
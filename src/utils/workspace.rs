@@ -0,0 +1,176 @@
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use toml_edit::{value, Array, DocumentMut, Item};
+
+use crate::error::{Context, Error, Result};
+use crate::log_utils::log_command_to_console;
+
+/// Returns the path to the Cargo.toml file of a workspace.
+///
+/// # Arguments
+///
+/// `workspace_dir` - The path to the workspace's directory.
+pub fn get_path_to_workspace_manifest(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("Cargo.toml")
+}
+
+/// Indicates whether a directory is the root of a cargo workspace, which it
+/// is if its Cargo.toml file has a `[workspace]` table.
+///
+/// # Arguments
+///
+/// `dir` - The path to the directory to check.
+pub fn is_workspace_root(dir: &Path) -> bool {
+    read_to_string(get_path_to_workspace_manifest(dir))
+        .ok()
+        .and_then(|manifest_string| toml::from_str::<toml::Value>(&manifest_string).ok())
+        .is_some_and(|manifest| manifest.get("workspace").is_some())
+}
+
+/// Returns the directories of the members of a workspace as cargo resolves
+/// them, which takes globs and excluded members into account.
+///
+/// # Arguments
+///
+/// `workspace_dir` - The path to the workspace's directory.
+pub fn get_workspace_member_dirs(workspace_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut metadata_command = Command::new("cargo");
+    metadata_command
+        .arg("metadata")
+        .arg("--format-version")
+        .arg("1")
+        .arg("--no-deps")
+        .current_dir(workspace_dir);
+    log_command_to_console(&metadata_command);
+    let output = metadata_command
+        .output()
+        .context("Unable to run cargo metadata to find the members of the workspace")?;
+    if !output.status.success() {
+        return Err(Error::CargoFailed {
+            message: "Unable to find the members of the workspace with cargo metadata".to_string(),
+            status: Some(output.status),
+            source: None,
+        });
+    }
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .context("Unable to parse the output of cargo metadata")?;
+
+    let member_ids: Vec<&str> = metadata["workspace_members"]
+        .as_array()
+        .map(|member_ids| member_ids.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+
+    Ok(metadata["packages"]
+        .as_array()
+        .map(|packages| packages.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|package| {
+            package["id"]
+                .as_str()
+                .is_some_and(|id| member_ids.contains(&id))
+        })
+        .filter_map(|package| package["manifest_path"].as_str())
+        .filter_map(|manifest_path| Path::new(manifest_path).parent())
+        .map(Path::to_path_buf)
+        .collect())
+}
+
+/// Adds a member to the `members` of a workspace's Cargo.toml file if it
+/// isn't already a member. The file is edited as a TOML document so that the
+/// rest of it, including comments and formatting, is left as is.
+///
+/// # Arguments
+///
+/// `workspace_dir` - The path to the workspace's directory.
+/// `member_dir` - The path to the member's directory.
+pub fn add_member_to_workspace(workspace_dir: &Path, member_dir: &Path) -> Result<()> {
+    let canonical_member_dir = dunce::canonicalize(member_dir).unwrap_or(member_dir.to_path_buf());
+    let is_member = get_workspace_member_dirs(workspace_dir)?
+        .iter()
+        .any(|member| {
+            dunce::canonicalize(member).is_ok_and(|member| member == canonical_member_dir)
+        });
+    if is_member {
+        return Ok(());
+    }
+
+    let member = member_dir
+        .strip_prefix(workspace_dir)
+        .unwrap_or(member_dir)
+        .to_string_lossy()
+        .replace('\\', "/");
+
+    let manifest_path = get_path_to_workspace_manifest(workspace_dir);
+    let manifest_string =
+        read_to_string(&manifest_path).context("Unable to read the workspace's Cargo.toml file")?;
+    let mut manifest = manifest_string
+        .parse::<DocumentMut>()
+        .context("Unable to parse the workspace's Cargo.toml file")?;
+
+    let workspace = manifest
+        .get_mut("workspace")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(|| {
+            Error::Invalid(format!(
+                "{} isn't a workspace because it doesn't have a [workspace] table",
+                manifest_path.display()
+            ))
+        })?;
+    let members = workspace
+        .entry("members")
+        .or_insert(value(Array::new()))
+        .as_array_mut()
+        .ok_or_else(|| {
+            Error::Invalid(format!(
+                "The workspace's members in {} have to be an array",
+                manifest_path.display()
+            ))
+        })?;
+
+    // Members of arrays that are written one per line are added on their
+    // own line with the same indentation as the last member.
+    let indent = members
+        .iter()
+        .last()
+        .and_then(|last_member| last_member.decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .and_then(|prefix| prefix.rfind('\n').map(|start| prefix[start..].to_string()));
+    match indent {
+        Some(indent) => {
+            // The whitespace after the last member has to be removed or the
+            // comma that separates it from the new member ends up on a line
+            // of its own.
+            if let Some(last_member) = members.iter_mut().last() {
+                let is_whitespace = last_member
+                    .decor()
+                    .suffix()
+                    .and_then(|suffix| suffix.as_str())
+                    .is_some_and(|suffix| suffix.trim().is_empty());
+                if is_whitespace {
+                    last_member.decor_mut().set_suffix("");
+                }
+            }
+
+            members.push(member);
+            if let Some(new_member) = members.iter_mut().last() {
+                new_member.decor_mut().set_prefix(indent);
+            }
+            members.set_trailing_comma(true);
+            if members
+                .trailing()
+                .as_str()
+                .is_some_and(|trailing| !trailing.contains('\n'))
+            {
+                members.set_trailing("\n");
+            }
+        }
+        None => members.push(member),
+    }
+
+    write(&manifest_path, manifest.to_string())
+        .context("Unable to update the workspace's Cargo.toml file")
+}
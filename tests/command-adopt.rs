@@ -0,0 +1,118 @@
+use assert_cmd::prelude::*;
+
+use serde_json::{json, Value};
+use std::env::set_current_dir;
+use std::error::Error;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+use std::process::Command;
+
+mod test_utilities;
+use test_utilities::{cleanup_test_files, init_test};
+
+/// Creates a library with classes written by hand, adopts them, and checks
+/// that they were added to the config and have gdns files.
+#[test]
+fn adopt_hand_written_classes() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Level");
+    cmd_create.assert().success();
+
+    // 3. Write classes by hand, one of which already has a gdns file that
+    // was moved in the Godot project.
+    write(
+        "src/characters.rs",
+        r#"use gdnative::api::{KinematicBody2D, Node2D};
+use gdnative::prelude::*;
+
+#[derive(gdnative::NativeClass)]
+#[inherit(KinematicBody2D)]
+pub struct Player;
+
+#[derive(Default)]
+pub struct Stats;
+
+#[derive(NativeClass)]
+#[inherit(Node2D)]
+pub struct Enemy;
+"#,
+    )?;
+    write(
+        "src/hud.rs",
+        "#[derive(NativeClass)]\n#[inherit(Control)]\npub struct Hud;\n",
+    )?;
+    create_dir_all("../platformer/ui")?;
+    write("../platformer/ui/hud.gdns", "")?;
+
+    // 4. Assert that the scan command was successful.
+    let mut cmd_scan = Command::new("cargo");
+    cmd_scan
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("scan");
+    cmd_scan.assert().success();
+
+    // 5. Assert that the classes were added to the config.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(
+        config_json["modules"],
+        json!(["Level", "Player", "Enemy", "Hud"])
+    );
+    assert_eq!(
+        config_json["module_base_classes"],
+        json!({
+            "Enemy": "Node2D",
+            "Hud": "Control",
+            "Level": "Node2D",
+            "Player": "KinematicBody2D"
+        })
+    );
+    assert_eq!(
+        config_json["module_files"],
+        json!({ "Enemy": "src/characters.rs", "Player": "src/characters.rs" })
+    );
+
+    // 6. Assert that the destroy command leaves the code of adopted classes.
+    let mut cmd_destroy = Command::new("cargo");
+    cmd_destroy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("destroy")
+        .arg("Enemy");
+    cmd_destroy.assert().success();
+    assert!(Path::new("src/characters.rs").exists());
+
+    set_current_dir("../")?;
+
+    // 7. Assert that gdns files were only created for the classes without one.
+    let player_gdns_string = read_to_string("platformer/gdnative/player.gdns")?;
+    assert!(player_gdns_string.contains("resource_name = \"Player\""));
+    assert!(!Path::new("platformer/gdnative/enemy.gdns").exists());
+    assert!(!Path::new("platformer/gdnative/hud.gdns").exists());
+    assert!(!Path::new("platformer/gdnative/stats.gdns").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
@@ -1,12 +1,14 @@
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 
 use std::env::set_current_dir;
 use std::error::Error;
+use std::fs::{create_dir_all, read_to_string, write};
 use std::path::Path;
 use std::process::Command;
 
 mod test_utilities;
-use test_utilities::{cleanup_test_files, init_test};
+use test_utilities::{cleanup_test_files, init_test, init_test_nested_godot_project_dir};
 
 /// Creates a library and a module and runs the build command ands checks to
 /// make sure that the default debug build was created.
@@ -52,7 +54,7 @@ fn build_default_to_debug_build() -> Result<(), Box<dyn Error>> {
         std::env::consts::DLL_SUFFIX
     );
 
-    assert_eq!(Path::new(&dynamic_library_debug_path).exists(), true);
+    assert!(Path::new(&dynamic_library_debug_path).exists());
 
     set_current_dir("../")?;
 
@@ -106,7 +108,7 @@ fn build_create_release_build() -> Result<(), Box<dyn Error>> {
         std::env::consts::DLL_SUFFIX
     );
 
-    assert_eq!(Path::new(&dynamic_library_debug_path).exists(), true);
+    assert!(Path::new(&dynamic_library_debug_path).exists());
 
     set_current_dir("../")?;
 
@@ -163,7 +165,803 @@ fn build_godot_project() -> Result<(), Box<dyn Error>> {
     );
 
     let dynamic_library_path = Path::new(&dynamic_library_name);
-    assert_eq!(dynamic_library_path.exists(), true);
+    assert!(dynamic_library_path.exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and runs the build command for a platform that hasn't
+/// been added and checks to make sure that it fails before building.
+#[test]
+fn build_fails_for_platform_not_added() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the build command failed because windows hasn't been
+    // added to the library.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--platform")
+        .arg("windows");
+    cmd_build
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "The platform windows hasn't been added",
+        ));
+
+    set_current_dir("../")?;
+
+    // 3. Assert that nothing was built.
+    assert!(!Path::new("platformer_modules/target").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library with a broken Cargo.toml file and runs the build command
+/// and checks to make sure that it fails without copying anything over to
+/// the Godot project.
+#[test]
+fn build_fails_when_cargo_fails() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Break the library's Cargo.toml file so that cargo fails.
+    write("Cargo.toml", "[package")?;
+
+    // 3. Assert that the build command failed because cargo failed.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build");
+    cmd_build
+        .assert()
+        .failure()
+        .code(4)
+        .stdout(predicate::str::contains("Build complete").not());
+
+    set_current_dir("../")?;
+
+    // 4. Assert that no dynamic library was copied over.
+    assert!(!Path::new("platformer/gdnative/bin").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library without the gdnative dependency so that it builds
+/// quickly and runs versioned builds, checking that each changed build gets
+/// its own copy of the dynamic library that the gdnlib file points to.
+#[test]
+fn build_versioned_dynamic_library() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the copies of the dynamic
+    // library are being tested.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        cargo_toml
+            .lines()
+            .filter(|line| !line.starts_with("gdnative"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 1 }\n",
+    )?;
+
+    let dynamic_library_path = |version: &str| {
+        format!(
+            "../platformer/gdnative/bin/{}/{}platformer_modules{}{}",
+            std::env::consts::OS.to_lowercase(),
+            std::env::consts::DLL_PREFIX,
+            version,
+            std::env::consts::DLL_SUFFIX
+        )
+    };
+
+    // 3. Assert that the first versioned build creates the first copy and
+    // points the gdnlib file to it.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--versioned");
+    cmd_build.assert().success();
+
+    assert!(Path::new(&dynamic_library_path(".1")).exists());
+    let gdnlib = read_to_string("../platformer/gdnative/platformer_modules.gdnlib")?;
+    assert!(gdnlib.contains(&dynamic_library_path(".1").replace("../platformer", "res:/")));
+
+    // 4. Assert that a changed build creates a new copy and removes the old
+    // one.
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 2 }\n",
+    )?;
+    cmd_build.assert().success();
+
+    assert!(Path::new(&dynamic_library_path(".2")).exists());
+    assert!(!Path::new(&dynamic_library_path(".1")).exists());
+    let gdnlib = read_to_string("../platformer/gdnative/platformer_modules.gdnlib")?;
+    assert!(gdnlib.contains(&dynamic_library_path(".2").replace("../platformer", "res:/")));
+
+    // 5. Assert that a build that isn't versioned points the gdnlib file back
+    // to the unversioned dynamic library.
+    let mut cmd_build_unversioned = Command::new("cargo");
+    cmd_build_unversioned
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build");
+    cmd_build_unversioned.assert().success();
+
+    assert!(Path::new(&dynamic_library_path("")).exists());
+    assert!(!Path::new(&dynamic_library_path(".2")).exists());
+    let gdnlib = read_to_string("../platformer/gdnative/platformer_modules.gdnlib")?;
+    assert!(gdnlib.contains(&dynamic_library_path("").replace("../platformer", "res:/")));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library without the gdnative dependency and rewrites its gdnlib
+/// file the way Godot saves it, with bare dotted keys, and checks that
+/// builds only change the native platform's entry.
+#[test]
+fn build_with_godot_formatted_gdnlib() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the gdnlib file is being
+    // tested.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        cargo_toml
+            .lines()
+            .filter(|line| !line.starts_with("gdnative"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 1 }\n",
+    )?;
+
+    // 3. Save the gdnlib file the way Godot does, with the native platform's
+    // entry pointing somewhere else.
+    let native_entry_name = match std::env::consts::OS {
+        "windows" => "Windows.64",
+        "macos" => "OSX.64",
+        _ => "X11.64",
+    };
+    let dynamic_library_res_path = |version: &str| {
+        format!(
+            "res://gdnative/bin/{}/{}platformer_modules{}{}",
+            std::env::consts::OS.to_lowercase(),
+            std::env::consts::DLL_PREFIX,
+            version,
+            std::env::consts::DLL_SUFFIX
+        )
+    };
+    let gdnlib_path = "../platformer/gdnative/platformer_modules.gdnlib";
+    let godot_gdnlib = format!(
+        "[general]\n\nsingleton=false\nload_once=true\nsymbol_prefix=\"godot_\"\nreloadable=true\n\n[entry]\n\nAndroid.arm64-v8a=\"res://custom/libplatformer_modules.so\"\n{}=\"res://old/platformer_modules\"\n\n[dependencies]\n\nAndroid.arm64-v8a=[  ]\n{}=[  ]\n",
+        native_entry_name, native_entry_name
+    );
+    write(gdnlib_path, &godot_gdnlib)?;
+
+    // 4. Assert that a build points the native platform's entry to the
+    // dynamic library and leaves the rest of the file as Godot saved it.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build");
+    cmd_build.assert().success();
+
+    let gdnlib = read_to_string(gdnlib_path)?;
+    assert_eq!(
+        gdnlib,
+        godot_gdnlib.replace(
+            "res://old/platformer_modules",
+            &dynamic_library_res_path("")
+        )
+    );
+
+    // 5. Assert that a versioned build can update the entry too.
+    let mut cmd_build_versioned = Command::new("cargo");
+    cmd_build_versioned
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--versioned");
+    cmd_build_versioned.assert().success();
+
+    let gdnlib = read_to_string(gdnlib_path)?;
+    assert_eq!(
+        gdnlib,
+        godot_gdnlib.replace(
+            "res://old/platformer_modules",
+            &dynamic_library_res_path(".1")
+        )
+    );
+
+    // 6. Assert that an unversioned build still succeeds when the gdnlib file
+    // can't be updated.
+    write(gdnlib_path, "[general]\n\nsingleton=false\n")?;
+    cmd_build
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Unable to point the gdnlib file to the dynamic library",
+        ));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library with a custom cargo profile and runs the build command
+/// with the profile and extra cargo arguments and checks to make sure that
+/// the dynamic library was built with both and copied to the Godot project.
+#[test]
+fn build_custom_profile_with_cargo_args() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the profile and the
+    // arguments are being tested, and add the profile and a feature.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        format!(
+            "{}\n\n[features]\nextra = []\n\n[profile.dev-opt]\ninherits = \"dev\"\nopt-level = 1\n",
+            cargo_toml
+                .lines()
+                .filter(|line| !line.starts_with("gdnative"))
+                .collect::<Vec<&str>>()
+                .join("\n")
+        ),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[cfg(not(feature = \"extra\"))]\ncompile_error!(\"the extra feature is required\");\n",
+    )?;
+
+    // 3. Assert that the build command was successful, which it only is if
+    // the feature was enabled.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--profile")
+        .arg("dev-opt")
+        .arg("--")
+        .arg("--features")
+        .arg("extra");
+    cmd_build
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("`dev-opt` profile"));
+
+    // 4. Assert the dynamic library was built under the profile's directory
+    // and copied over.
+    let dynamic_library_file_name = format!(
+        "{}platformer_modules{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    assert!(Path::new("target")
+        .join(env!("TARGET"))
+        .join("dev-opt")
+        .join(&dynamic_library_file_name)
+        .exists());
+    assert!(Path::new("../platformer/gdnative/bin")
+        .join(std::env::consts::OS.to_lowercase())
+        .join(&dynamic_library_file_name)
+        .exists());
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and runs the build command with `CARGO_TARGET_DIR` set
+/// and checks to make sure that the dynamic library was found in that target
+/// directory and copied to the Godot project.
+#[test]
+fn build_with_custom_target_dir() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the location of the
+    // dynamic library is being tested.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        cargo_toml
+            .lines()
+            .filter(|line| !line.starts_with("gdnative"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 1 }\n",
+    )?;
+
+    // 3. Assert that the build command was successful. The cli is run
+    // directly so that the target directory only applies to the library.
+    let mut cmd_build = Command::new(env!("CARGO_BIN_EXE_godot-rust-cli"));
+    cmd_build
+        .arg("build")
+        .env("CARGO_TARGET_DIR", "custom_target");
+    cmd_build.assert().success();
+
+    // 4. Assert that the dynamic library was built in the custom target
+    // directory and copied over.
+    let dynamic_library_file_name = format!(
+        "{}platformer_modules{}",
+        std::env::consts::DLL_PREFIX,
+        std::env::consts::DLL_SUFFIX
+    );
+    assert!(!Path::new("target").exists());
+    assert!(Path::new("custom_target")
+        .join(env!("TARGET"))
+        .join("debug")
+        .join(&dynamic_library_file_name)
+        .exists());
+    assert!(Path::new("../platformer/gdnative/bin")
+        .join(std::env::consts::OS.to_lowercase())
+        .join(&dynamic_library_file_name)
+        .exists());
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates two libraries in a workspace along with a crate that isn't a
+/// library and runs the build command from the root of the workspace and
+/// checks to make sure that both libraries were built and copied to the
+/// Godot project.
+#[test]
+fn build_workspace_libraries() -> Result<(), Box<dyn Error>> {
+    init_test_nested_godot_project_dir();
+
+    write("games/Cargo.toml", "[workspace]\nmembers = []\n")?;
+
+    // 1. Assert that the new command was successful for both libraries.
+    for (name, is_plugin) in &[("PlatformerModules", false), ("LevelEditor", true)] {
+        let mut cmd_new = Command::new("cargo");
+        cmd_new
+            .arg("run")
+            .arg("--manifest-path=../Cargo.toml")
+            .arg("new")
+            .arg(name)
+            .arg("games/platformer")
+            .arg("--workspace")
+            .arg("games")
+            .arg("--skip-build");
+        if *is_plugin {
+            cmd_new.arg("--plugin");
+        }
+        cmd_new.assert().success();
+    }
+
+    set_current_dir("games")?;
+
+    // 2. Add a crate that isn't a library and remove the gdnative dependency
+    // from the libraries since only the copies of the dynamic libraries are
+    // being tested.
+    let mut cmd_cargo_new = Command::new("cargo");
+    cmd_cargo_new.arg("new").arg("--lib").arg("core");
+    cmd_cargo_new.assert().success();
+
+    for library in &["platformer_modules", "level_editor"] {
+        let cargo_toml_path = format!("{}/Cargo.toml", library);
+        let cargo_toml = read_to_string(&cargo_toml_path)?;
+        write(
+            &cargo_toml_path,
+            cargo_toml
+                .lines()
+                .filter(|line| !line.starts_with("gdnative"))
+                .collect::<Vec<&str>>()
+                .join("\n"),
+        )?;
+        write(
+            format!("{}/src/lib.rs", library),
+            format!(
+                "#[unsafe(no_mangle)]\npub extern \"C\" fn {}() -> i32 {{ 1 }}\n",
+                library
+            ),
+        )?;
+    }
+
+    // 3. Assert that the build command was successful from the root of the
+    // workspace.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build");
+    cmd_build.assert().success();
+
+    // 4. Assert that both dynamic libraries were copied over.
+    let platform = std::env::consts::OS.to_lowercase();
+    let dynamic_library_file_name = |library: &str| {
+        format!(
+            "{}{}{}",
+            std::env::consts::DLL_PREFIX,
+            library,
+            std::env::consts::DLL_SUFFIX
+        )
+    };
+    assert!(Path::new("platformer/gdnative/bin")
+        .join(&platform)
+        .join(dynamic_library_file_name("platformer_modules"))
+        .exists());
+    assert!(Path::new("platformer/addons/level_editor/gdnative/bin")
+        .join(&platform)
+        .join(dynamic_library_file_name("level_editor"))
+        .exists());
+
+    // 5. Assert that a workspace can't be watched.
+    let mut cmd_watch = Command::new("cargo");
+    cmd_watch
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--watch");
+    cmd_watch.assert().failure().code(1);
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library with another platform and runs the build command for
+/// all platforms and checks to make sure that the output of each build was
+/// logged to a file and summarized in a table. The other platform's build
+/// needs cross so only the native platform's build is expected to succeed.
+#[test]
+fn build_all_platforms_logs_and_summarizes() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the builds are being
+    // tested and add the windows platform to the config without setting up
+    // its docker image.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        cargo_toml
+            .lines()
+            .filter(|line| !line.starts_with("gdnative"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 1 }\n",
+    )?;
+    let mut config: serde_json::Value =
+        serde_json::from_str(&read_to_string("godot-rust-cli.json")?)?;
+    config["platforms"] = serde_json::json!(["windows"]);
+    write("godot-rust-cli.json", config.to_string())?;
+
+    // 3. Assert that the builds were summarized in a table.
+    let native_platform = std::env::consts::OS.to_lowercase();
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--all")
+        .arg("--jobs")
+        .arg("2");
+    cmd_build.assert().stdout(
+        predicate::str::contains("Platform")
+            .and(predicate::str::contains("Duration"))
+            .and(
+                predicate::str::is_match(format!(r"(?m)^{}\s+dev\s+.*succeeded$", native_platform))
+                    .unwrap(),
+            ),
+    );
+
+    // 4. Assert that the output of each build was logged.
+    assert!(Path::new("target/godot-rust-cli")
+        .join(format!("{}.log", native_platform))
+        .exists());
+    assert!(Path::new("target/godot-rust-cli/windows.log").exists());
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library with the android platform and runs the build command
+/// for it without an Android NDK and then with a fake Android SDK and checks
+/// to make sure that the build only gets past finding the NDK's linker once
+/// the newest NDK in the SDK has one.
+#[test]
+fn build_android_finds_ndk_in_sdk() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the build command is being
+    // tested and add the android.arm64 platform to the config.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        cargo_toml
+            .lines()
+            .filter(|line| !line.starts_with("gdnative"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 1 }\n",
+    )?;
+    let mut config: serde_json::Value =
+        serde_json::from_str(&read_to_string("godot-rust-cli.json")?)?;
+    config["platforms"] = serde_json::json!(["android.arm64"]);
+    write("godot-rust-cli.json", config.to_string())?;
+
+    // 3. Assert that the build fails before building when there's no NDK.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--platform")
+        .arg("android.arm64")
+        .env_remove("ANDROID_NDK_HOME")
+        .env_remove("ANDROID_SDK_ROOT")
+        .env_remove("ANDROID_HOME");
+    cmd_build
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains("Unable to find the Android NDK"));
+
+    // 4. Create an Android SDK with an older NDK that has no linker and a
+    // newer one that does.
+    let host_tag = match std::env::consts::OS {
+        "macos" => "darwin-x86_64",
+        "windows" => "windows-x86_64",
+        _ => "linux-x86_64",
+    };
+    let sdk_dir = std::env::current_dir()?.join("android-sdk");
+    let ndk_bin_dir = |version: &str| {
+        sdk_dir
+            .join("ndk")
+            .join(version)
+            .join("toolchains/llvm/prebuilt")
+            .join(host_tag)
+            .join("bin")
+    };
+    create_dir_all(ndk_bin_dir("25.2.9519653"))?;
+    create_dir_all(ndk_bin_dir("25.10.0"))?;
+    let clang = if cfg!(windows) {
+        "aarch64-linux-android21-clang.cmd"
+    } else {
+        "aarch64-linux-android21-clang"
+    };
+    write(ndk_bin_dir("25.10.0").join(clang), "")?;
+
+    // 5. Assert that the NDK's linker was found and the build got as far as
+    // cargo, which fails since the android target isn't expected to be
+    // installed and the linker is empty.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--platform")
+        .arg("android.arm64")
+        .env_remove("ANDROID_NDK_HOME")
+        .env_remove("ANDROID_HOME")
+        .env("ANDROID_SDK_ROOT", &sdk_dir);
+    cmd_build
+        .assert()
+        .failure()
+        .code(4)
+        .stderr(predicate::str::contains("Android NDK").not());
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library with windows built with the local-linker backend and
+/// runs the build command for it without a linker configured and checks to
+/// make sure that it fails before building.
+#[test]
+fn build_local_linker_needs_configured_linker() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Remove the gdnative dependency since only the build command is being
+    // tested and add windows with the local-linker backend to the config.
+    let cargo_toml = read_to_string("Cargo.toml")?;
+    write(
+        "Cargo.toml",
+        cargo_toml
+            .lines()
+            .filter(|line| !line.starts_with("gdnative"))
+            .collect::<Vec<&str>>()
+            .join("\n"),
+    )?;
+    write(
+        "src/lib.rs",
+        "#[unsafe(no_mangle)]\npub extern \"C\" fn version() -> i32 { 1 }\n",
+    )?;
+    let mut config: serde_json::Value =
+        serde_json::from_str(&read_to_string("godot-rust-cli.json")?)?;
+    config["platforms"] = serde_json::json!(["windows"]);
+    config["platform_backends"] = serde_json::json!({ "windows": "local-linker" });
+    write("godot-rust-cli.json", config.to_string())?;
+
+    // 3. Assert that the build command failed because there's no linker for
+    // windows.
+    let mut cmd_build = Command::new("cargo");
+    cmd_build
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("build")
+        .arg("--platform")
+        .arg("windows")
+        .env_remove("CARGO_TARGET_X86_64_PC_WINDOWS_GNU_LINKER");
+    cmd_build
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "no linker is configured for x86_64-pc-windows-gnu",
+        ));
+
+    set_current_dir("../")?;
 
     cleanup_test_files();
 
@@ -3,7 +3,7 @@ use assert_cmd::prelude::*;
 use serde_json::{json, Value};
 use std::env::set_current_dir;
 use std::error::Error;
-use std::fs::read_to_string;
+use std::fs::{create_dir_all, read_to_string, write};
 use std::path::Path;
 use std::process::Command;
 
@@ -40,7 +40,7 @@ fn create_module_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 3. Assert that the module has a mod file.
     let module_mod_path = Path::new("src/player.rs");
-    assert_eq!(module_mod_path.exists(), true);
+    assert!(module_mod_path.exists());
 
     // 4. Assert that the module contents are what we expect.
     let module_mod_string = read_to_string(module_mod_path)?;
@@ -110,7 +110,7 @@ fn create_module_godot_structure() -> Result<(), Box<dyn Error>> {
 
     // 3. Assert that the gdns file for the module was created.
     let module_gdns_path = Path::new("platformer/gdnative/player.gdns");
-    assert_eq!(module_gdns_path.exists(), true);
+    assert!(module_gdns_path.exists());
 
     // 4. Assert that the gnds file has the correct contents.
     let module_gdns_string = read_to_string(module_gdns_path)?;
@@ -178,9 +178,9 @@ fn create_multiple_modules_library_structure() -> Result<(), Box<dyn Error>> {
     let player_module_mod_path = Path::new("src/player.rs");
     let enemy_module_mod_path = Path::new("src/enemy.rs");
     let level_module_mod_path = Path::new("src/level.rs");
-    assert_eq!(player_module_mod_path.exists(), true);
-    assert_eq!(enemy_module_mod_path.exists(), true);
-    assert_eq!(level_module_mod_path.exists(), true);
+    assert!(player_module_mod_path.exists());
+    assert!(enemy_module_mod_path.exists());
+    assert!(level_module_mod_path.exists());
 
     // 4. Assert that the modules were added to the lib file.
     let lib_file_string = read_to_string("src/lib.rs")?;
@@ -197,11 +197,11 @@ fn create_multiple_modules_library_structure() -> Result<(), Box<dyn Error>> {
     );
     assert_eq!(
         lib_file_split[7].trim(),
-        "handle.add_class::<level::Level>();"
+        "handle.add_class::<enemy::Enemy>();"
     );
     assert_eq!(
         lib_file_split[8].trim(),
-        "handle.add_class::<enemy::Enemy>();"
+        "handle.add_class::<level::Level>();"
     );
 
     // 5: Assert that the modules were added to the config.
@@ -266,9 +266,9 @@ fn create_multiple_modules_godot_structure() -> Result<(), Box<dyn Error>> {
     let player_module_gdns_path = Path::new("platformer/gdnative/player.gdns");
     let enemy_module_gdns_path = Path::new("platformer/gdnative/enemy.gdns");
     let level_module_gdns_path = Path::new("platformer/gdnative/level.gdns");
-    assert_eq!(player_module_gdns_path.exists(), true);
-    assert_eq!(enemy_module_gdns_path.exists(), true);
-    assert_eq!(level_module_gdns_path.exists(), true);
+    assert!(player_module_gdns_path.exists());
+    assert!(enemy_module_gdns_path.exists());
+    assert!(level_module_gdns_path.exists());
 
     // 4. Assert that the gnds files have the correct contents.
     let player_module_gdns_string = read_to_string(player_module_gdns_path)?;
@@ -311,3 +311,563 @@ fn create_multiple_modules_godot_structure() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Creates a GDExtension library and then creates a module within it and
+/// checks that the module is a GDExtension class with no gdns file.
+#[test]
+fn create_gdextension_module_structure() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build")
+        .arg("--api")
+        .arg("gdextension");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create.assert().success();
+
+    // 3. Assert that the module is a GDExtension class.
+    let module_mod_string = read_to_string("src/player.rs")?;
+    let module_mod_split = module_mod_string
+        .split("\n")
+        .map(|x| x.replace("\r", ""))
+        .collect::<Vec<String>>();
+    assert_eq!(
+        module_mod_split[0],
+        "use godot::classes::{INode2D, Node2D};"
+    );
+    assert_eq!(module_mod_split[3], "#[derive(GodotClass)]");
+    assert_eq!(module_mod_split[4], "#[class(base=Node2D)]");
+    assert_eq!(module_mod_split[5], "pub struct Player {");
+
+    // 4. Assert that only the mod statement was added to the lib file.
+    let lib_file_string = read_to_string("src/lib.rs")?;
+    let lib_file_split = lib_file_string
+        .split("\n")
+        .map(|x| x.replace("\r", ""))
+        .collect::<Vec<String>>();
+    assert_eq!(lib_file_split[0], "mod player;");
+    assert!(!lib_file_string.contains("add_class"));
+
+    set_current_dir("../")?;
+
+    // 5. Assert that no gdns file was created for the module.
+    assert!(!Path::new("platformer/gdnative/player.gdns").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library, hand edits its lib file, and then creates modules
+/// within the library and checks that the lib file was updated correctly.
+#[test]
+fn create_module_hand_edited_lib() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Hand edit the lib file to have an inline module, a comment, and a
+    // registration that is already split across lines.
+    write(
+        "src/lib.rs",
+        r#"// The helpers for the modules.
+pub mod helpers {}
+
+use gdnative::prelude::*;
+
+fn init(godot: InitHandle) {
+    // The player.
+    godot.add_class::<
+        player::Player,
+    >();
+}
+
+godot_init!(init);
+"#,
+    )?;
+
+    // 3. Assert that the create commands were successful.
+    let mut cmd_create_player = Command::new("cargo");
+    cmd_create_player
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create_player.assert().success();
+
+    let mut cmd_create_enemy = Command::new("cargo");
+    cmd_create_enemy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Enemy");
+    cmd_create_enemy.assert().success();
+
+    // 4. Assert that the modules were added to the lib file once and that
+    // the hand edits were kept.
+    let lib_file_string = read_to_string("src/lib.rs")?;
+    let lib_file_split = lib_file_string
+        .split("\n")
+        .map(|x| x.replace("\r", ""))
+        .collect::<Vec<String>>();
+    assert_eq!(lib_file_split[0], "// The helpers for the modules.");
+    assert_eq!(lib_file_split[1], "pub mod helpers {}");
+    assert_eq!(lib_file_split[2], "mod enemy;");
+    assert_eq!(lib_file_split[3], "mod player;");
+    assert_eq!(lib_file_split[7], "fn init(godot: InitHandle) {");
+    assert_eq!(lib_file_split[8].trim(), "// The player.");
+    assert_eq!(
+        lib_file_split[9].trim(),
+        "godot.add_class::<player::Player>();"
+    );
+    assert_eq!(
+        lib_file_split[10].trim(),
+        "godot.add_class::<enemy::Enemy>();"
+    );
+    assert_eq!(lib_file_split[11], "}");
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates modules that inherit from different Godot classes and checks that
+/// they have the lifecycle methods for their class.
+#[test]
+fn create_module_inherits() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create commands were successful.
+    let mut cmd_create_player = Command::new("cargo");
+    cmd_create_player
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player")
+        .arg("--inherits")
+        .arg("KinematicBody2D");
+    cmd_create_player.assert().success();
+
+    let mut cmd_create_hud = Command::new("cargo");
+    cmd_create_hud
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Hud")
+        .arg("--inherits")
+        .arg("control");
+    cmd_create_hud.assert().success();
+
+    let mut cmd_create_stats = Command::new("cargo");
+    cmd_create_stats
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Stats")
+        .arg("--inherits")
+        .arg("Resource");
+    cmd_create_stats.assert().success();
+
+    // 3. Assert that the create command fails for an unknown class.
+    let mut cmd_create_unknown = Command::new("cargo");
+    cmd_create_unknown
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Enemy")
+        .arg("--inherits")
+        .arg("CharacterBody2D");
+    cmd_create_unknown.assert().failure();
+    assert!(!Path::new("src/enemy.rs").exists());
+
+    // 4. Assert that the modules have the lifecycle methods for their class.
+    let player_module_string = read_to_string("src/player.rs")?;
+    assert!(player_module_string.contains("#[inherit(KinematicBody2D)]"));
+    assert!(player_module_string.contains("fn _ready(&mut self, _owner: &KinematicBody2D)"));
+    assert!(player_module_string
+        .contains("fn _physics_process(&mut self, _owner: &KinematicBody2D, _delta: f32)"));
+    assert!(!player_module_string.contains("fn _process"));
+
+    let hud_module_string = read_to_string("src/hud.rs")?;
+    assert!(hud_module_string.contains("use gdnative::api::{Control, InputEvent};"));
+    assert!(hud_module_string
+        .contains("fn _gui_input(&mut self, _owner: &Control, _event: Ref<InputEvent>)"));
+
+    let stats_module_string = read_to_string("src/stats.rs")?;
+    assert!(stats_module_string.contains("fn new(_owner: &Resource) -> Self"));
+    assert!(!stats_module_string.contains("fn _ready"));
+    assert!(!stats_module_string.contains("fn _process"));
+
+    // 5. Assert that the config has the base class of each module.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!(["Player", "Hud", "Stats"]));
+    assert_eq!(
+        config_json["module_base_classes"],
+        json!({ "Hud": "Control", "Player": "KinematicBody2D", "Stats": "Resource" })
+    );
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates modules from a template in the library's templates directory and
+/// from a global template and checks that the placeholders were replaced.
+#[test]
+fn create_module_from_template() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Create a template in the library and one in the global templates
+    // directory.
+    create_dir_all("templates")?;
+    write(
+        "templates/custom.rs",
+        "// {{library}}/{{snake}}\n#[derive(NativeClass)]\n#[inherit({{base}})]\npub struct {{pascal}};\n",
+    )?;
+    create_dir_all("xdg/godot-rust-cli/templates")?;
+    write(
+        "xdg/godot-rust-cli/templates/global.rs",
+        "pub struct {{pascal}};\n",
+    )?;
+
+    // 3. Assert that the create commands were successful.
+    let mut cmd_create_player = Command::new("cargo");
+    cmd_create_player
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("MainPlayer")
+        .arg("--inherits")
+        .arg("KinematicBody2D")
+        .arg("--template")
+        .arg("custom")
+        .env("XDG_CONFIG_HOME", "xdg");
+    cmd_create_player.assert().success();
+
+    let mut cmd_create_enemy = Command::new("cargo");
+    cmd_create_enemy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Enemy")
+        .arg("--template")
+        .arg("global")
+        .env("XDG_CONFIG_HOME", "xdg");
+    cmd_create_enemy.assert().success();
+
+    // 4. Assert that the create command fails for a template that doesn't
+    // exist.
+    let mut cmd_create_level = Command::new("cargo");
+    cmd_create_level
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Level")
+        .arg("--template")
+        .arg("missing")
+        .env("XDG_CONFIG_HOME", "xdg");
+    cmd_create_level.assert().failure();
+    assert!(!Path::new("src/level.rs").exists());
+
+    // 5. Assert that the placeholders in the templates were replaced.
+    let player_module_string = read_to_string("src/main_player.rs")?;
+    assert_eq!(
+        player_module_string,
+        "// platformer_modules/main_player\n#[derive(NativeClass)]\n#[inherit(KinematicBody2D)]\npub struct MainPlayer;\n"
+    );
+
+    let enemy_module_string = read_to_string("src/enemy.rs")?;
+    assert_eq!(enemy_module_string, "pub struct Enemy;\n");
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates modules with scenes and checks that the scenes use the module's
+/// gdns file as the script of their root node.
+#[test]
+fn create_module_with_scene() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create commands were successful.
+    let mut cmd_create_player = Command::new("cargo");
+    cmd_create_player
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player")
+        .arg("--scene");
+    cmd_create_player.assert().success();
+
+    let mut cmd_create_hud = Command::new("cargo");
+    cmd_create_hud
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Hud")
+        .arg("--inherits")
+        .arg("Control")
+        .arg("--scene")
+        .arg("scenes");
+    cmd_create_hud.assert().success();
+
+    // 3. Assert that a scene can't be created for a module that isn't a node.
+    let mut cmd_create_stats = Command::new("cargo");
+    cmd_create_stats
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Stats")
+        .arg("--inherits")
+        .arg("Resource")
+        .arg("--scene");
+    cmd_create_stats.assert().failure();
+
+    set_current_dir("../")?;
+
+    // 4. Assert that the scenes were created with the module as their script.
+    let player_scene_string = read_to_string("platformer/gdnative/player.tscn")?;
+    assert_eq!(
+        player_scene_string,
+        r#"[gd_scene load_steps=2 format=2]
+
+[ext_resource path="res://gdnative/player.gdns" type="Script" id=1]
+
+[node name="Player" type="Node2D"]
+script = ExtResource( 1 )
+"#
+    );
+
+    let hud_scene_string = read_to_string("platformer/scenes/hud.tscn")?;
+    assert!(hud_scene_string
+        .contains(r#"[ext_resource path="res://gdnative/hud.gdns" type="Script" id=1]"#));
+    assert!(hud_scene_string.contains(r#"[node name="Hud" type="Control"]"#));
+
+    // 5. Assert that the config has the path to the scenes.
+    let config = read_to_string("platformer_modules/godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(
+        config_json["module_scenes"],
+        json!({ "Hud": "scenes/hud.tscn", "Player": "gdnative/player.tscn" })
+    );
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Tries to create modules that can't be created and checks that the create
+/// command fails with the right exit code.
+#[test]
+fn create_module_errors() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that creating a module outside of the library directory
+    // fails with the config missing exit code.
+    let mut cmd_create_outside = Command::new("cargo");
+    cmd_create_outside
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create_outside.assert().failure().code(2);
+
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create.assert().success();
+
+    // 2. Assert that creating a module that already exists fails.
+    let mut cmd_create_duplicate = Command::new("cargo");
+    cmd_create_duplicate
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create_duplicate.assert().failure().code(1);
+
+    set_current_dir("../")?;
+
+    // 3. Assert that the module is only in the config once.
+    let config = read_to_string("platformer_modules/godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!(["Player"]));
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a module with the json message format and checks that every line
+/// written to stdout is an event and that the module and the error from
+/// creating it again are both reported.
+#[test]
+fn create_module_with_json_message_format() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful and that every line
+    // that it wrote to stdout is an event.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player")
+        .arg("--message-format")
+        .arg("json");
+    let create_output = cmd_create.assert().success().get_output().stdout.clone();
+    let create_events = String::from_utf8(create_output)?
+        .lines()
+        .map(serde_json::from_str::<Value>)
+        .collect::<Result<Vec<Value>, _>>()?;
+
+    // 3. Assert that the module was reported with the path to its file.
+    let module_created = create_events
+        .iter()
+        .find(|event| event["event"] == "module_created")
+        .expect("The module_created event wasn't reported");
+    assert_eq!(module_created["name"], "Player");
+    assert!(module_created["path"]
+        .as_str()
+        .is_some_and(|path| path.ends_with("player.rs")));
+
+    // 4. Assert that creating the module again reports the error along with
+    // the exit code.
+    let mut cmd_create_again = Command::new("cargo");
+    cmd_create_again
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("--")
+        .arg("--message-format")
+        .arg("json")
+        .arg("create")
+        .arg("Player");
+    let create_again_output = cmd_create_again
+        .assert()
+        .failure()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let create_again_events = String::from_utf8(create_again_output)?
+        .lines()
+        .map(serde_json::from_str::<Value>)
+        .collect::<Result<Vec<Value>, _>>()?;
+    assert_eq!(
+        create_again_events.last().map(|event| &event["event"]),
+        Some(&json!("error"))
+    );
+    assert_eq!(
+        create_again_events.last().map(|event| &event["code"]),
+        Some(&json!(1))
+    );
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use assert_cmd::prelude::*;
 use serde_json::{json, Value};
 use std::env::set_current_dir;
 use std::error::Error;
-use std::fs::read_to_string;
+use std::fs::{read_to_string, write};
 use std::path::Path;
 use std::process::Command;
 
@@ -49,7 +49,7 @@ fn destroy_module_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 4. Assert that the module no longer has a mod file.
     let module_mod_path = Path::new("src/player.rs");
-    assert_eq!(module_mod_path.exists(), false);
+    assert!(!module_mod_path.exists());
 
     // 5. Assert that the module was removed from the lib file.
     let lib_file_string = read_to_string("src/lib.rs")?;
@@ -116,7 +116,7 @@ fn destroy_module_godot_structure() -> Result<(), Box<dyn Error>> {
 
     // 4. Assert that the module no longer has a gdns file.
     let module_gdns_path = Path::new("platformer/gdnative/player.gdns");
-    assert_eq!(module_gdns_path.exists(), false);
+    assert!(!module_gdns_path.exists());
 
     Ok(())
 }
@@ -197,15 +197,15 @@ fn destroy_modules_library_structure() -> Result<(), Box<dyn Error>> {
     let player_module_mod_path = Path::new("src/player.rs");
     let level_module_mod_path = Path::new("src/level.rs");
     let environment_module_mod_path = Path::new("src/environment.rs");
-    assert_eq!(player_module_mod_path.exists(), true);
-    assert_eq!(level_module_mod_path.exists(), true);
-    assert_eq!(environment_module_mod_path.exists(), true);
+    assert!(player_module_mod_path.exists());
+    assert!(level_module_mod_path.exists());
+    assert!(environment_module_mod_path.exists());
 
     // 5. Assert that the modules that were deleted no longer have mod files.
     let enemy_module_mod_path = Path::new("src/enemy.rs");
     let space_module_mod_path = Path::new("src/space.rs");
-    assert_eq!(enemy_module_mod_path.exists(), false);
-    assert_eq!(space_module_mod_path.exists(), false);
+    assert!(!enemy_module_mod_path.exists());
+    assert!(!space_module_mod_path.exists());
 
     // 6. Assert that the modules were removed from the lib file.
     let lib_file_string = read_to_string("src/lib.rs")?;
@@ -225,11 +225,11 @@ fn destroy_modules_library_structure() -> Result<(), Box<dyn Error>> {
     );
     assert_eq!(
         lib_file_split[7].trim(),
-        "handle.add_class::<environment::Environment>();"
+        "handle.add_class::<level::Level>();"
     );
     assert_eq!(
         lib_file_split[8].trim(),
-        "handle.add_class::<level::Level>();"
+        "handle.add_class::<environment::Environment>();"
     );
     assert_eq!(lib_file_split[9], "}");
     assert_eq!(lib_file_split[10], "");
@@ -328,15 +328,15 @@ fn destroy_modules_godot_structure() -> Result<(), Box<dyn Error>> {
     let player_gdns_path = Path::new("platformer/gdnative/player.gdns");
     let level_gdns_path = Path::new("platformer/gdnative/level.gdns");
     let environment_gdns_path = Path::new("platformer/gdnative/environment.gdns");
-    assert_eq!(player_gdns_path.exists(), true);
-    assert_eq!(level_gdns_path.exists(), true);
-    assert_eq!(environment_gdns_path.exists(), true);
+    assert!(player_gdns_path.exists());
+    assert!(level_gdns_path.exists());
+    assert!(environment_gdns_path.exists());
 
     // 4. Assert that the modules that were deleted no longer have a gdns file.
     let enemy_gdns_path = Path::new("platformer/gdnative/enemy.gdns");
     let space_gdns_path = Path::new("platformer/gdnative/space.gdns");
-    assert_eq!(enemy_gdns_path.exists(), false);
-    assert_eq!(space_gdns_path.exists(), false);
+    assert!(!enemy_gdns_path.exists());
+    assert!(!space_gdns_path.exists());
 
     Ok(())
 }
@@ -395,8 +395,205 @@ fn destroy_moved_module_godot_structure() -> Result<(), Box<dyn Error>> {
     let module_gdns_path_old = Path::new("platformer/gdnative/player.gdns");
     let module_gdns_path_new = Path::new("platformer/player/player.gdns");
 
-    assert_eq!(module_gdns_path_old.exists(), false);
-    assert_eq!(module_gdns_path_new.exists(), false);
+    assert!(!module_gdns_path_old.exists());
+    assert!(!module_gdns_path_new.exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and a module, hand edits the lib file, and then destroys
+/// the module and checks that every reference to it was removed.
+#[test]
+fn destroy_module_hand_edited_lib() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create.assert().success();
+
+    // 3. Hand edit the lib file so that the module is public and is also
+    // registered inside of a loop.
+    write(
+        "src/lib.rs",
+        r#"pub mod player;
+
+use gdnative::prelude::*;
+
+fn init(handle: InitHandle) {
+    for _ in 0..1 {
+        handle.add_class::<player::Player>();
+    }
+}
+
+godot_init!(init);
+"#,
+    )?;
+
+    // 4. Assert that the destroy command was successful.
+    let mut cmd_destroy = Command::new("cargo");
+    cmd_destroy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("destroy")
+        .arg("Player");
+    cmd_destroy.assert().success();
+
+    // 5. Assert that the module was removed from the lib file.
+    let lib_file_string = read_to_string("src/lib.rs")?;
+    assert!(!lib_file_string.contains("mod player;"));
+    assert!(!lib_file_string.contains("player::Player"));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates modules with scenes, destroys them, and checks that the scene is
+/// only removed when the user agrees to it.
+#[test]
+fn destroy_module_with_scene() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create commands were successful.
+    for module_name in ["Player", "Enemy"] {
+        let mut cmd_create = Command::new("cargo");
+        cmd_create
+            .arg("run")
+            .arg("--manifest-path=../../Cargo.toml")
+            .arg("create")
+            .arg(module_name)
+            .arg("--scene");
+        cmd_create.assert().success();
+    }
+
+    // 3. Assert that the destroy commands were successful.
+    let mut cmd_destroy_player = Command::new("cargo");
+    cmd_destroy_player
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("destroy")
+        .arg("Player");
+    assert_cmd::Command::from_std(cmd_destroy_player)
+        .write_stdin("y\n")
+        .assert()
+        .success();
+
+    let mut cmd_destroy_enemy = Command::new("cargo");
+    cmd_destroy_enemy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("destroy")
+        .arg("Enemy");
+    assert_cmd::Command::from_std(cmd_destroy_enemy)
+        .write_stdin("n\n")
+        .assert()
+        .success();
+
+    set_current_dir("../")?;
+
+    // 4. Assert that only the scene the user agreed to remove was removed.
+    assert!(!Path::new("platformer/gdnative/player.tscn").exists());
+    assert!(Path::new("platformer/gdnative/enemy.tscn").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a module with a scene using a lowercase name and destroys it with
+/// the same name, checking that its scene is found and removed.
+#[test]
+fn destroy_lowercase_module_with_scene() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful and that the module
+    // and its scene are saved by the module's PascalCase name.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("player")
+        .arg("--scene");
+    cmd_create.assert().success();
+
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!(["Player"]));
+    assert_eq!(
+        config_json["module_scenes"],
+        json!({ "Player": "gdnative/player.tscn" })
+    );
+
+    // 3. Assert that the destroy command was successful.
+    let mut cmd_destroy = Command::new("cargo");
+    cmd_destroy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("destroy")
+        .arg("player");
+    assert_cmd::Command::from_std(cmd_destroy)
+        .write_stdin("y\n")
+        .assert()
+        .success();
+
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!([]));
+    assert_eq!(config_json["module_scenes"], json!({}));
+
+    set_current_dir("../")?;
+
+    // 4. Assert that the scene was removed.
+    assert!(!Path::new("platformer/gdnative/player.tscn").exists());
 
     cleanup_test_files();
 
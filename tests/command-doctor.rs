@@ -0,0 +1,278 @@
+use assert_cmd::prelude::*;
+
+use serde_json::{json, Value};
+use std::env::set_current_dir;
+use std::error::Error;
+use std::fs::{read_to_string, remove_file, write};
+use std::process::Command;
+
+mod test_utilities;
+use test_utilities::{cleanup_test_files, init_test};
+
+/// Creates a library and a module and checks that the doctor command doesn't
+/// find any problems with it.
+#[test]
+fn doctor_no_problems() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create.assert().success();
+
+    // 3. Assert that the doctor command was successful.
+    let mut cmd_doctor = Command::new("cargo");
+    cmd_doctor
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor");
+    cmd_doctor.assert().success();
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and a module, removes the module's file, and checks
+/// that the doctor command reports it and removes the module when fixing.
+#[test]
+fn doctor_fix_missing_module_file() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create.assert().success();
+
+    remove_file("src/player.rs")?;
+
+    // 3. Assert that the doctor command finds the problem.
+    let mut cmd_doctor = Command::new("cargo");
+    cmd_doctor
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor");
+    cmd_doctor.assert().failure();
+
+    // 4. Assert that the doctor command fixes the problem.
+    let mut cmd_doctor_fix = Command::new("cargo");
+    cmd_doctor_fix
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor")
+        .arg("--fix");
+    cmd_doctor_fix.assert().success();
+
+    // 5. Assert that the module was removed from the config and lib file.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!([]));
+
+    let lib_file_string = read_to_string("src/lib.rs")?;
+    assert!(!lib_file_string.contains("player"));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library, registers a class in the lib file by hand, and checks
+/// that the doctor command adds it to the config when fixing.
+#[test]
+fn doctor_fix_module_not_in_config() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Register a module in the lib file without the create command.
+    write("src/enemy.rs", "")?;
+    write(
+        "src/lib.rs",
+        r#"mod enemy;
+use gdnative::prelude::*;
+fn init(handle: InitHandle) {
+    handle.add_class::<enemy::Enemy>();
+}
+godot_init!(init);
+"#,
+    )?;
+
+    // 3. Assert that the doctor command finds the problem.
+    let mut cmd_doctor = Command::new("cargo");
+    cmd_doctor
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor");
+    cmd_doctor.assert().failure();
+
+    // 4. Assert that the doctor command fixes the problem.
+    let mut cmd_doctor_fix = Command::new("cargo");
+    cmd_doctor_fix
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor")
+        .arg("--fix");
+    cmd_doctor_fix.assert().success();
+
+    // 5. Assert that the module was added to the config.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!(["Enemy"]));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and a module, points the module's gdns file at a gdnlib
+/// file that doesn't exist, and checks that the doctor command fixes it.
+#[test]
+fn doctor_fix_gdns_missing_gdnlib() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create command was successful.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player");
+    cmd_create.assert().success();
+
+    let gdns_path = "../platformer/gdnative/player.gdns";
+    let gdns_contents = read_to_string(gdns_path)?.replace(
+        "res://gdnative/platformer_modules.gdnlib",
+        "res://old/platformer_modules.gdnlib",
+    );
+    write(gdns_path, gdns_contents)?;
+
+    // 3. Assert that the doctor command finds the problem.
+    let mut cmd_doctor = Command::new("cargo");
+    cmd_doctor
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor");
+    cmd_doctor.assert().failure();
+
+    // 4. Assert that the doctor command fixes the problem.
+    let mut cmd_doctor_fix = Command::new("cargo");
+    cmd_doctor_fix
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor")
+        .arg("--fix");
+    cmd_doctor_fix.assert().success();
+
+    // 5. Assert that the gdns file points to the gdnlib file again.
+    let gdns_contents = read_to_string(gdns_path)?;
+    assert!(gdns_contents.contains(
+        r#"[ext_resource path="res://gdnative/platformer_modules.gdnlib" type="GDNativeLibrary" id=1]"#
+    ));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library, removes the Godot project's project.godot file, and
+/// checks that the doctor command fails even when fixing.
+#[test]
+fn doctor_missing_godot_project() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    remove_file("../platformer/project.godot")?;
+
+    // 2. Assert that the doctor command can't fix the problem.
+    let mut cmd_doctor_fix = Command::new("cargo");
+    cmd_doctor_fix
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("doctor")
+        .arg("--fix");
+    cmd_doctor_fix.assert().failure();
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use assert_cmd::prelude::*;
 
 use serde_json::{json, Value};
 use std::error::Error;
-use std::fs::read_to_string;
+use std::fs::{read_to_string, write};
 use std::path::Path;
 use std::process::Command;
 
@@ -27,7 +27,7 @@ fn new_create_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 2. Assert that the library directory was created.
     let library_dir = Path::new("platformer_modules");
-    assert_eq!(library_dir.exists(), true);
+    assert!(library_dir.exists());
 
     // 3: Assert that the initial config is what it should be.
     let config = read_to_string("platformer_modules/godot-rust-cli.json")?;
@@ -44,7 +44,7 @@ fn new_create_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 4. Assert that the lib file exists.
     let lib_file_path = Path::new("platformer_modules/src/lib.rs");
-    assert_eq!(lib_file_path.exists(), true);
+    assert!(lib_file_path.exists());
 
     // 5. Assert that the lib file is what it should be.
     let lib_file_string = read_to_string(lib_file_path)?;
@@ -110,19 +110,19 @@ fn new_create_godot_structure() -> Result<(), Box<dyn Error>> {
         std::env::consts::DLL_SUFFIX
     );
     let dynamic_library_path = Path::new(&dynamic_library_name);
-    assert_eq!(dynamic_library_path.exists(), true);
+    assert!(dynamic_library_path.exists());
 
     // 3. Assert that the gdnlib file exists.
     let gdnlib_path = Path::new("platformer/gdnative/platformer_modules.gdnlib");
-    assert_eq!(gdnlib_path.exists(), true);
+    assert!(gdnlib_path.exists());
 
     // 4. Assert that the contents of the gdnlib file are what we expect.
     let gdnlib_string = read_to_string(gdnlib_path)?;
     let gdnlib_toml: Gdnlib = toml::from_str(&gdnlib_string)?;
-    assert_eq!(gdnlib_toml.general.singleton, false);
-    assert_eq!(gdnlib_toml.general.load_once, true);
+    assert!(!gdnlib_toml.general.singleton);
+    assert!(gdnlib_toml.general.load_once);
     assert_eq!(gdnlib_toml.general.symbol_prefix, "godot_");
-    assert_eq!(gdnlib_toml.general.reloadable, true);
+    assert!(gdnlib_toml.general.reloadable);
 
     assert_eq!(
         gdnlib_toml.entry.get("Android.x86_64"),
@@ -136,6 +136,17 @@ fn new_create_godot_structure() -> Result<(), Box<dyn Error>> {
             &"res://gdnative/bin/android/aarch64-linux-android/libplatformer_modules.so".to_owned()
         )
     );
+    assert_eq!(
+        gdnlib_toml.entry.get("Android.armeabi-v7a"),
+        Some(
+            &"res://gdnative/bin/android/armv7-linux-androideabi/libplatformer_modules.so"
+                .to_owned()
+        )
+    );
+    assert_eq!(
+        gdnlib_toml.entry.get("Android.x86"),
+        Some(&"res://gdnative/bin/android/i686-linux-android/libplatformer_modules.so".to_owned())
+    );
     assert_eq!(
         gdnlib_toml.entry.get("Windows.64"),
         Some(&"res://gdnative/bin/windows/platformer_modules.dll".to_owned())
@@ -188,11 +199,303 @@ fn new_nested_godot_project_dir() -> Result<(), Box<dyn Error>> {
         std::env::consts::DLL_SUFFIX
     );
     let dynamic_library_path = Path::new(&dynamic_library_name);
-    assert_eq!(dynamic_library_path.exists(), true);
+    assert!(dynamic_library_path.exists());
 
     // 3. Assert that the gdnlib file exists.
     let gdnlib_path = Path::new("games/platformer/gdnative/platformer_modules.gdnlib");
-    assert_eq!(gdnlib_path.exists(), true);
+    assert!(gdnlib_path.exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a GDExtension library and checks that the library and the Godot
+/// project are set up for Godot 4.
+#[test]
+fn new_create_gdextension_library_structure() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build")
+        .arg("--api")
+        .arg("gdextension");
+    cmd.assert().success();
+
+    // 2. Assert that the api was saved to the config.
+    let config = read_to_string("platformer_modules/godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["api"], "gdextension");
+
+    // 3. Assert that the lib file has the GDExtension entry point.
+    let lib_file_string = read_to_string("platformer_modules/src/lib.rs")?;
+    let lib_file_split = lib_file_string
+        .split("\n")
+        .map(|x| x.replace("\r", ""))
+        .collect::<Vec<String>>();
+    assert_eq!(lib_file_split[0], "use godot::prelude::*;");
+    assert_eq!(lib_file_split[2], "struct PlatformerModules;");
+    assert_eq!(lib_file_split[4], "#[gdextension]");
+    assert_eq!(
+        lib_file_split[5],
+        "unsafe impl ExtensionLibrary for PlatformerModules {}"
+    );
+
+    // 4. Assert that the godot dependency is used instead of gdnative.
+    let cargo_toml_string = read_to_string("platformer_modules/Cargo.toml")?;
+    assert!(cargo_toml_string.contains("godot = \"0.2.4\""));
+    assert!(!cargo_toml_string.contains("gdnative"));
+
+    // 5. Assert that the gdextension file was created instead of a gdnlib file.
+    let gdextension_path = Path::new("platformer/gdnative/platformer_modules.gdextension");
+    assert!(gdextension_path.exists());
+    assert!(!Path::new("platformer/gdnative/platformer_modules.gdnlib").exists());
+
+    let gdextension_string = read_to_string(gdextension_path)?;
+    assert!(gdextension_string.contains("entry_symbol = \"gdext_rust_init\""));
+    assert!(gdextension_string
+        .contains("linux.debug.x86_64 = \"res://gdnative/bin/linux/libplatformer_modules.so\""));
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Tries to create a library for a directory that isn't a Godot project and
+/// checks that it fails with the exit code for an invalid Godot project.
+#[test]
+fn new_invalid_godot_project() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command fails with the invalid Godot project exit code.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("not_a_godot_project")
+        .arg("--skip-build");
+    cmd.assert().failure().code(3);
+
+    // 2. Assert that the library directory wasn't created.
+    assert!(!Path::new("platformer_modules").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Tries to create a library for a Godot project in a directory that doesn't
+/// exist and checks that it fails with the exit code for an invalid Godot
+/// project instead of panicking.
+#[test]
+fn new_missing_godot_project_dir() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command fails with the invalid Godot project exit code.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("./missing/project")
+        .arg("--skip-build");
+    cmd.assert().failure().code(3);
+
+    // 2. Assert that the library directory wasn't created.
+    assert!(!Path::new("platformer_modules").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library as a member of a workspace and checks that it was
+/// created in the workspace and added to its members.
+#[test]
+fn new_create_library_in_workspace() -> Result<(), Box<dyn Error>> {
+    init_test_nested_godot_project_dir();
+
+    write("games/Cargo.toml", "[workspace]\nmembers = []\n")?;
+
+    // 1. Assert that the new command was successful.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("games/platformer")
+        .arg("--workspace")
+        .arg("games")
+        .arg("--skip-build");
+    cmd.assert().success();
+
+    // 2. Assert that the library was created in the workspace.
+    assert!(Path::new("games/platformer_modules/godot-rust-cli.json").exists());
+    assert!(!Path::new("platformer_modules").exists());
+
+    // 3. Assert that the library was added to the workspace's members.
+    let workspace_cargo_toml: toml::Value = toml::from_str(&read_to_string("games/Cargo.toml")?)?;
+    assert_eq!(
+        workspace_cargo_toml["workspace"]["members"],
+        toml::Value::Array(vec![toml::Value::String("platformer_modules".to_string())])
+    );
+
+    // 4. Assert that the gdnlib file was created in the Godot project.
+    assert!(Path::new("games/platformer/gdnative/platformer_modules.gdnlib").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library as a member of a workspace that doesn't list its
+/// members yet, which cargo doesn't add the library to by itself, and checks
+/// that the library was added without changing the rest of the workspace's
+/// Cargo.toml file.
+#[test]
+fn new_create_library_in_workspace_without_members() -> Result<(), Box<dyn Error>> {
+    init_test_nested_godot_project_dir();
+
+    write(
+        "games/Cargo.toml",
+        "# The games and their libraries.\n[workspace]\nresolver = \"2\" # Needed for edition 2021.\n",
+    )?;
+
+    // 1. Assert that the new command was successful.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("games/platformer")
+        .arg("--workspace")
+        .arg("games")
+        .arg("--skip-build");
+    cmd.assert().success();
+
+    // 2. Assert that the library was added to the workspace's members and
+    // that the rest of the file was kept.
+    let workspace_cargo_toml_string = read_to_string("games/Cargo.toml")?;
+    let workspace_cargo_toml: toml::Value = toml::from_str(&workspace_cargo_toml_string)?;
+    assert_eq!(
+        workspace_cargo_toml["workspace"]["members"],
+        toml::Value::Array(vec![toml::Value::String("platformer_modules".to_string())])
+    );
+    assert!(workspace_cargo_toml_string.starts_with("# The games and their libraries.\n[workspace]\nresolver = \"2\" # Needed for edition 2021.\n"));
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library as a member of a workspace that has package fields for
+/// its members to inherit and checks that the inherited fields are kept.
+#[test]
+fn new_create_library_in_workspace_with_inherited_fields() -> Result<(), Box<dyn Error>> {
+    init_test_nested_godot_project_dir();
+
+    write(
+        "games/Cargo.toml",
+        "[workspace]\nmembers = []\n\n[workspace.package]\nversion = \"0.2.0\"\nauthors = [\"Platformer Team\"]\nedition = \"2021\"\n",
+    )?;
+
+    // 1. Assert that the new command was successful.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("games/platformer")
+        .arg("--workspace")
+        .arg("games")
+        .arg("--skip-build");
+    cmd.assert().success();
+
+    // 2. Assert that the library still inherits the fields from the
+    // workspace.
+    let library_cargo_toml: toml::Value =
+        toml::from_str(&read_to_string("games/platformer_modules/Cargo.toml")?)?;
+    for field in ["version", "authors", "edition"] {
+        assert_eq!(
+            library_cargo_toml["package"][field]["workspace"],
+            toml::Value::Boolean(true)
+        );
+    }
+    assert!(library_cargo_toml["dependencies"].get("gdnative").is_some());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Tries to create a library in a directory that isn't a workspace and
+/// checks that it fails without creating the library.
+#[test]
+fn new_invalid_workspace() -> Result<(), Box<dyn Error>> {
+    init_test_nested_godot_project_dir();
+
+    // 1. Assert that the new command failed.
+    let mut cmd = Command::new("cargo");
+    cmd.arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("games/platformer")
+        .arg("--workspace")
+        .arg("games")
+        .arg("--skip-build");
+    cmd.assert().failure().code(1);
+
+    // 2. Assert that the library wasn't created.
+    assert!(!Path::new("games/platformer_modules").exists());
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library verbosely and then a module quietly and checks that the
+/// commands that were run were only logged for the verbose one.
+#[test]
+fn new_create_library_verbose_and_quiet() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful and that it logged the
+    // cargo command that created the library.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build")
+        .arg("-v");
+    let new_output = cmd_new.assert().success().get_output().stdout.clone();
+    assert!(String::from_utf8(new_output)?.contains("Running: cargo new platformer_modules --lib"));
+
+    // 2. Assert that the create command was successful and that it didn't
+    // log anything.
+    let mut cmd_create = Command::new("cargo");
+    cmd_create
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player")
+        .arg("-q")
+        .current_dir("platformer_modules");
+    let create_output = cmd_create.assert().success().get_output().stdout.clone();
+    assert!(create_output.is_empty());
+
+    // 3. Assert that the module was still created.
+    assert!(Path::new("platformer_modules/src/player.rs").exists());
 
     cleanup_test_files();
 
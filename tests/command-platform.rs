@@ -1,9 +1,10 @@
 use assert_cmd::prelude::*;
+use predicates::prelude::*;
 
 use serde_json::{json, Value};
-use std::env::set_current_dir;
+use std::env::{current_dir, set_current_dir};
 use std::error::Error;
-use std::fs::read_to_string;
+use std::fs::{create_dir_all, read_to_string, write};
 use std::path::Path;
 use std::process::Command;
 
@@ -34,7 +35,7 @@ fn platform_add_unsupported_platform() -> Result<(), Box<dyn Error>> {
         .arg("run")
         .arg("--manifest-path=../../Cargo.toml")
         .arg("add-platform")
-        .arg("macos");
+        .arg("playstation");
     cmd_add_platform.assert().failure();
 
     // 3. Assert that the config contains the added platform.
@@ -85,14 +86,14 @@ fn platform_add_windows_platform() -> Result<(), Box<dyn Error>> {
     // 4. Since the windows platform needs a custom docker image we want to
     // make sure that the docker image was copied over.
     let windows_docker_file_path = Path::new("docker/Dockerfile.x86_64-pc-windows-gnu");
-    assert_eq!(windows_docker_file_path.exists(), true);
+    assert!(windows_docker_file_path.exists());
 
     // 5. Assert that the Cross.toml file exists and that its contents are what
     // we expect.
     let cross_config_file_path = Path::new("Cross.toml");
-    assert_eq!(cross_config_file_path.exists(), true);
+    assert!(cross_config_file_path.exists());
 
-    let cross_config_string = read_to_string(&cross_config_file_path)?;
+    let cross_config_string = read_to_string(cross_config_file_path)?;
     let cross_config_split = cross_config_string
         .split("\n")
         .map(|x| x.replace("\r", ""))
@@ -197,12 +198,12 @@ fn platform_add_windows_platform_and_build_default_debug() -> Result<(), Box<dyn
     // 4. Assert the default debug build was created.
     let dynamic_library_debug_path =
         Path::new("target/x86_64-pc-windows-gnu/debug/platformer_modules.dll");
-    assert_eq!(dynamic_library_debug_path.exists(), true);
+    assert!(dynamic_library_debug_path.exists());
 
     // 5. Assert that the dll file was copied to the Godot project.
     let godot_project_dll_path =
         Path::new("../platformer/gdnative/bin/windows/platformer_modules.dll");
-    assert_eq!(godot_project_dll_path.exists(), true);
+    assert!(godot_project_dll_path.exists());
 
     set_current_dir("../")?;
 
@@ -253,12 +254,12 @@ fn platform_add_windows_platform_and_build_release() -> Result<(), Box<dyn Error
     // 4. Assert the default release build was created.
     let dynamic_library_release_path =
         Path::new("target/x86_64-pc-windows-gnu/release/platformer_modules.dll");
-    assert_eq!(dynamic_library_release_path.exists(), true);
+    assert!(dynamic_library_release_path.exists());
 
     // 5. Assert that the dll file was copied to the Godot project.
     let godot_project_dll_path =
         Path::new("../platformer/gdnative/bin/windows/platformer_modules.dll");
-    assert_eq!(godot_project_dll_path.exists(), true);
+    assert!(godot_project_dll_path.exists());
 
     set_current_dir("../")?;
 
@@ -321,3 +322,278 @@ fn platform_add_and_remove_platform() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Creates a library and adds macos, which is built with cargo instead of
+/// cross, and checks that no cross configuration or docker image was added.
+#[test]
+fn platform_add_cargo_platform() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new_library = Command::new("cargo");
+    cmd_new_library
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new_library.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the add platform command was successful.
+    let mut cmd_add_platform = Command::new("cargo");
+    cmd_add_platform
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("add-platform")
+        .arg("MacOS");
+    cmd_add_platform.assert().success();
+
+    // 3. Assert that the config contains the added platform.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["platforms"], json!(["macos"]));
+
+    // 4. Assert that nothing was set up for cross.
+    assert!(!Path::new("Cross.toml").exists());
+    assert!(!Path::new("docker").exists());
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and adds android, which adds every Android ABI, and
+/// checks that the renamed android.arm is still accepted.
+#[test]
+fn platform_add_android_platforms() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new_library = Command::new("cargo");
+    cmd_new_library
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new_library.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that android.arm adds the platform that it was renamed to.
+    let mut cmd_add_android_arm = Command::new("cargo");
+    cmd_add_android_arm
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("add-platform")
+        .arg("android.arm");
+    cmd_add_android_arm.assert().success();
+
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["platforms"], json!(["android.arm64"]));
+
+    // 3. Assert that android adds the rest of the Android platforms and skips
+    // the one that was already added.
+    let mut cmd_add_android = Command::new("cargo");
+    cmd_add_android
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("add-platform")
+        .arg("android");
+    cmd_add_android
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "android.arm64 is already in the config, skipping it",
+        ));
+
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(
+        config_json["platforms"],
+        json!([
+            "android.arm64",
+            "android.armv7",
+            "android.x86_64",
+            "android.x86"
+        ])
+    );
+
+    // 4. Assert that adding android again fails since every platform that it
+    // stands for was added.
+    cmd_add_android.assert().failure();
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and adds html5 with the Emscripten SDK at `EMSDK` and
+/// checks that the SDK on the host is used instead of setting up cross, and
+/// that removing it doesn't remove docker images.
+#[test]
+fn platform_add_html5_platform_with_emsdk() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new_library = Command::new("cargo");
+    cmd_new_library
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new_library.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Create an Emscripten SDK with just emcc in it.
+    let emsdk_dir = current_dir()?.join("emsdk");
+    let emcc_dir = emsdk_dir.join("upstream").join("emscripten");
+    create_dir_all(&emcc_dir)?;
+    write(
+        emcc_dir.join(if cfg!(windows) { "emcc.bat" } else { "emcc" }),
+        "",
+    )?;
+
+    // 3. Assert that the add platform command was successful and that it
+    // uses the Emscripten SDK.
+    let mut cmd_add_platform = Command::new("cargo");
+    cmd_add_platform
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("add-platform")
+        .arg("html5")
+        .env("EMSDK", &emsdk_dir);
+    cmd_add_platform.assert().success().stdout(
+        predicate::str::contains("html5 is built with").and(predicate::str::contains(
+            "rustup toolchain install nightly --component rust-src",
+        )),
+    );
+
+    // 4. Assert that the config contains the added platform.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["platforms"], json!(["html5"]));
+
+    // 5. Assert that nothing was set up for cross.
+    assert!(!Path::new("Cross.toml").exists());
+    assert!(!Path::new("docker").exists());
+
+    // 6. Assert that removing the platform doesn't try to remove the docker
+    // image that was never built.
+    let mut cmd_remove_platform = Command::new("cargo");
+    cmd_remove_platform
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("remove-platform")
+        .arg("html5")
+        .arg("-v");
+    cmd_remove_platform
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("rmi").not());
+
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["platforms"], json!([]));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and adds windows with the zigbuild backend and checks
+/// that the backend is saved without setting up cross, and that it's
+/// removed along with the platform.
+#[test]
+fn platform_add_platform_with_backend() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new_library = Command::new("cargo");
+    cmd_new_library
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new_library.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that a backend that doesn't exist can't be chosen.
+    let mut cmd_add_platform = Command::new("cargo");
+    cmd_add_platform
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("add-platform")
+        .arg("windows")
+        .arg("--backend")
+        .arg("docker");
+    cmd_add_platform.assert().failure();
+
+    // 3. Assert that the add platform command was successful.
+    let mut cmd_add_platform = Command::new("cargo");
+    cmd_add_platform
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("add-platform")
+        .arg("windows")
+        .arg("--backend")
+        .arg("zigbuild");
+    cmd_add_platform
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cargo install cargo-zigbuild"));
+
+    // 4. Assert that the config contains the platform and its backend.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["platforms"], json!(["windows"]));
+    assert_eq!(
+        config_json["platform_backends"],
+        json!({ "windows": "zigbuild" })
+    );
+
+    // 5. Assert that nothing was set up for cross.
+    assert!(!Path::new("Cross.toml").exists());
+    assert!(!Path::new("docker").exists());
+
+    // 6. Assert that removing the platform removes its backend without
+    // trying to remove any docker images.
+    let mut cmd_remove_platform = Command::new("cargo");
+    cmd_remove_platform
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("remove-platform")
+        .arg("windows");
+    cmd_remove_platform
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed docker image").not());
+
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["platforms"], json!([]));
+    assert_eq!(config_json["platform_backends"], json!({}));
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
@@ -29,7 +29,7 @@ fn plugin_create_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 2. Assert that the library directory for the plugin was created.
     let plugin_library_dir = Path::new("directory_browser");
-    assert_eq!(plugin_library_dir.exists(), true);
+    assert!(plugin_library_dir.exists());
 
     set_current_dir(plugin_library_dir)?;
 
@@ -48,7 +48,7 @@ fn plugin_create_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 4. Assert that by default the plugin should have a module with the name of the plugin.
     let plugin_module_path = Path::new("src/directory_browser.rs");
-    assert_eq!(plugin_module_path.exists(), true);
+    assert!(plugin_module_path.exists());
 
     // 5. Assert that the contents of the plugin's initial module matches the initial tool module.
     let plugin_module_string = read_to_string(plugin_module_path)?;
@@ -67,7 +67,7 @@ fn plugin_create_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 6. Assert that the lib file exists.
     let lib_file_path = Path::new("src/lib.rs");
-    assert_eq!(lib_file_path.exists(), true);
+    assert!(lib_file_path.exists());
 
     // 7. Assert that the plugin's initial module is added to the lib file.
     let lib_file_string = read_to_string(lib_file_path)?;
@@ -106,7 +106,7 @@ fn plugin_create_godot_structure() -> Result<(), Box<dyn Error>> {
 
     // 2. Assert that the plugin directory in the Godot project was created.
     let plugin_godot_dir = Path::new("platformer/addons/directory_browser");
-    assert_eq!(plugin_godot_dir.exists(), true);
+    assert!(plugin_godot_dir.exists());
 
     // 3. Assert that the dynamic library for the plugin exists in the plugin's bin directory.
     let plugin_dynamic_library_name = format!(
@@ -116,15 +116,15 @@ fn plugin_create_godot_structure() -> Result<(), Box<dyn Error>> {
         std::env::consts::DLL_SUFFIX
     );
     let plugin_dynamic_library_path = Path::new(&plugin_dynamic_library_name);
-    assert_eq!(plugin_dynamic_library_path.exists(), true);
+    assert!(plugin_dynamic_library_path.exists());
 
     // 4. Assert that the `gdnative` directory was created in the plugin's directory.
     let plugin_gdnative_path = Path::new("platformer/addons/directory_browser/gdnative");
-    assert_eq!(plugin_gdnative_path.exists(), true);
+    assert!(plugin_gdnative_path.exists());
 
     // 5. Assert that the `plugin.cfg` file exists.
     let plugin_cfg_path = Path::new("platformer/addons/directory_browser/plugin.cfg");
-    assert_eq!(plugin_cfg_path.exists(), true);
+    assert!(plugin_cfg_path.exists());
 
     // 6. Assert that the contents of the `plugin.cfg` are what we expect.
     let plugin_cfg_string = read_to_string(plugin_cfg_path)?;
@@ -139,7 +139,7 @@ fn plugin_create_godot_structure() -> Result<(), Box<dyn Error>> {
     // 7. Assert that the plugin's gdns file exists.
     let plugin_gdns_path =
         Path::new("platformer/addons/directory_browser/gdnative/directory_browser.gdns");
-    assert_eq!(plugin_gdns_path.exists(), true);
+    assert!(plugin_gdns_path.exists());
 
     // 8. Assert that the contents of the plugin's gdns file are what we expect.
     let plugin_gdns_string = read_to_string(plugin_gdns_path)?;
@@ -154,15 +154,15 @@ fn plugin_create_godot_structure() -> Result<(), Box<dyn Error>> {
     // 9. Assert that the plugin's gdnlib file exists.
     let plugin_gdnlib_path =
         Path::new("platformer/addons/directory_browser/gdnative/directory_browser.gdnlib");
-    assert_eq!(plugin_gdnlib_path.exists(), true);
+    assert!(plugin_gdnlib_path.exists());
 
     // 10. Assert that the contents of the plugin's gdnlib file are what we expect.
     let gdnlib_string = read_to_string(plugin_gdnlib_path)?;
     let gdnlib_toml: Gdnlib = toml::from_str(&gdnlib_string)?;
-    assert_eq!(gdnlib_toml.general.singleton, false);
-    assert_eq!(gdnlib_toml.general.load_once, true);
+    assert!(!gdnlib_toml.general.singleton);
+    assert!(gdnlib_toml.general.load_once);
     assert_eq!(gdnlib_toml.general.symbol_prefix, "godot_");
-    assert_eq!(gdnlib_toml.general.reloadable, true);
+    assert!(gdnlib_toml.general.reloadable);
 
     assert_eq!(
         gdnlib_toml.entry.get("Android.x86_64"),
@@ -248,7 +248,7 @@ fn plugin_create_module_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 4. Assert that the plugin module has a mod file.
     let module_path = Path::new("src/explorer.rs");
-    assert_eq!(module_path.exists(), true);
+    assert!(module_path.exists());
 
     // 5. Assert that the contents of the module's file matches the initial tool module.
     let module_string = read_to_string(module_path)?;
@@ -275,11 +275,11 @@ fn plugin_create_module_library_structure() -> Result<(), Box<dyn Error>> {
     assert_eq!(lib_file_split[1], "mod explorer;");
     assert_eq!(
         lib_file_split[5].trim(),
-        "handle.add_tool_class::<explorer::Explorer>();"
+        "handle.add_tool_class::<directory_browser::DirectoryBrowser>();"
     );
     assert_eq!(
         lib_file_split[6].trim(),
-        "handle.add_tool_class::<directory_browser::DirectoryBrowser>();"
+        "handle.add_tool_class::<explorer::Explorer>();"
     );
 
     set_current_dir("../")?;
@@ -336,11 +336,11 @@ fn plugin_create_module_godot_structure() -> Result<(), Box<dyn Error>> {
         std::env::consts::DLL_SUFFIX
     );
     let plugin_dynamic_library_path = Path::new(&plugin_dynamic_library_name);
-    assert_eq!(plugin_dynamic_library_path.exists(), true);
+    assert!(plugin_dynamic_library_path.exists());
 
     // 4. Assert that the plugin's gdns file exists.
     let module_gdns_path = Path::new("platformer/addons/directory_browser/gdnative/explorer.gdns");
-    assert_eq!(module_gdns_path.exists(), true);
+    assert!(module_gdns_path.exists());
 
     // 8. Assert that the contents of the plugin's gdns file are what we expect.
     let module_gdns_string = read_to_string(module_gdns_path)?;
@@ -407,7 +407,7 @@ fn plugin_destroy_module_library_structure() -> Result<(), Box<dyn Error>> {
 
     // 4. Assert that the plugin module no longer has a mod file.
     let module_path = Path::new("src/explorer.rs");
-    assert_eq!(module_path.exists(), false);
+    assert!(!module_path.exists());
 
     // 5. Assert that the module is removed from the lib file.
     let lib_file_string = read_to_string("src/lib.rs")?;
@@ -471,7 +471,7 @@ fn plugin_destroy_module_godot_structure() -> Result<(), Box<dyn Error>> {
     // 4. Assert that the plugin's gdns file no longer exists.
     let module_gdns_path =
         Path::new("platformer/addons/directory_browser/rust_modules/explorer.gdns");
-    assert_eq!(module_gdns_path.exists(), false);
+    assert!(!module_gdns_path.exists());
 
     cleanup_test_files();
 
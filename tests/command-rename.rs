@@ -0,0 +1,151 @@
+use assert_cmd::prelude::*;
+
+use serde_json::{json, Value};
+use std::env::set_current_dir;
+use std::error::Error;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+use std::process::Command;
+
+mod test_utilities;
+use test_utilities::{cleanup_test_files, init_test};
+
+/// Creates a library and a module, references the module in a scene, renames
+/// the module, and checks that every reference to it was renamed.
+#[test]
+fn rename_module() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the create commands were successful.
+    let mut cmd_create_player = Command::new("cargo");
+    cmd_create_player
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Player")
+        .arg("--scene");
+    cmd_create_player.assert().success();
+
+    let mut cmd_create_enemy = Command::new("cargo");
+    cmd_create_enemy
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("create")
+        .arg("Enemy");
+    cmd_create_enemy.assert().success();
+
+    // 3. Reference the module in a resource in another directory.
+    create_dir_all("../platformer/resources")?;
+    write(
+        "../platformer/resources/player.tres",
+        "[gd_resource type=\"Resource\" load_steps=2 format=2]\n\n[ext_resource path=\"res://gdnative/player.gdns\" type=\"Script\" id=1]\n",
+    )?;
+
+    // 4. Assert that renaming a module to the name of another module fails.
+    let mut cmd_rename_existing = Command::new("cargo");
+    cmd_rename_existing
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("rename")
+        .arg("Player")
+        .arg("Enemy");
+    cmd_rename_existing.assert().failure();
+
+    // 5. Assert that the rename command was successful.
+    let mut cmd_rename = Command::new("cargo");
+    cmd_rename
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("rename")
+        .arg("Player")
+        .arg("MainHero");
+    cmd_rename.assert().success();
+
+    // 6. Assert that the module's file was renamed along with its struct.
+    assert!(!Path::new("src/player.rs").exists());
+    let module_string = read_to_string("src/main_hero.rs")?;
+    assert!(module_string.contains("pub struct MainHero;"));
+    assert!(module_string.contains("impl MainHero {"));
+    assert!(!module_string.contains("Player"));
+
+    // 7. Assert that the module was renamed in the lib file.
+    let lib_file_string = read_to_string("src/lib.rs")?;
+    assert!(lib_file_string.contains("mod main_hero;"));
+    assert!(lib_file_string.contains("handle.add_class::<main_hero::MainHero>();"));
+    assert!(!lib_file_string.contains("player"));
+
+    // 8. Assert that the module was renamed in the config.
+    let config = read_to_string("godot-rust-cli.json")?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!(["MainHero", "Enemy"]));
+    assert_eq!(
+        config_json["module_scenes"],
+        json!({ "MainHero": "gdnative/player.tscn" })
+    );
+
+    set_current_dir("../")?;
+
+    // 9. Assert that the gdns file was renamed.
+    assert!(!Path::new("platformer/gdnative/player.gdns").exists());
+    let gdns_string = read_to_string("platformer/gdnative/main_hero.gdns")?;
+    assert!(gdns_string.contains("resource_name = \"MainHero\""));
+    assert!(gdns_string.contains("class_name = \"MainHero\""));
+
+    // 10. Assert that the scene and resource point to the renamed gdns file.
+    let scene_string = read_to_string("platformer/gdnative/player.tscn")?;
+    assert!(scene_string.contains("path=\"res://gdnative/main_hero.gdns\""));
+    let resource_string = read_to_string("platformer/resources/player.tres")?;
+    assert!(resource_string.contains("path=\"res://gdnative/main_hero.gdns\""));
+
+    cleanup_test_files();
+
+    Ok(())
+}
+
+/// Creates a library and attempts to rename a module that doesn't exist.
+#[test]
+fn rename_module_not_in_config() -> Result<(), Box<dyn Error>> {
+    init_test();
+
+    // 1. Assert that the new command was successful.
+    let mut cmd_new = Command::new("cargo");
+    cmd_new
+        .arg("run")
+        .arg("--manifest-path=../Cargo.toml")
+        .arg("new")
+        .arg("PlatformerModules")
+        .arg("platformer")
+        .arg("--skip-build");
+    cmd_new.assert().success();
+
+    set_current_dir("platformer_modules")?;
+
+    // 2. Assert that the rename command fails.
+    let mut cmd_rename = Command::new("cargo");
+    cmd_rename
+        .arg("run")
+        .arg("--manifest-path=../../Cargo.toml")
+        .arg("rename")
+        .arg("Player")
+        .arg("Hero");
+    cmd_rename.assert().failure();
+
+    set_current_dir("../")?;
+
+    cleanup_test_files();
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, File};
+use std::path::{Path, PathBuf};
+
+use godot_rust_cli::{ModuleOptions, NewOptions, Project};
+
+/// Returns a directory to create the library and Godot project in for a
+/// test. The directory is created from scratch so that no files are left
+/// over from previous runs.
+///
+/// # Arguments
+///
+/// `name` - The name of the directory.
+fn create_test_dir(name: &str) -> PathBuf {
+    let test_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(name);
+    if test_dir.exists() {
+        remove_dir_all(&test_dir).expect("Unable to remove test dir");
+    }
+
+    create_dir_all(test_dir.join("platformer")).expect("Unable to create Godot project directory");
+    File::create(test_dir.join("platformer").join("project.godot"))
+        .expect("Unable to create godot.project file");
+
+    test_dir
+}
+
+/// Creates a library and a module through the library api and checks that
+/// everything is created relative to the directories passed in rather than
+/// the current directory.
+#[test]
+fn project_create_library_and_module() -> Result<(), Box<dyn Error>> {
+    let test_dir = create_test_dir("project_create_library_and_module");
+
+    // 1. Create the library with the Godot project relative to the test dir.
+    let project = Project::new(
+        &test_dir,
+        "PlatformerModules",
+        Path::new("platformer"),
+        NewOptions::default(),
+    )?;
+    let library_dir = test_dir.join("platformer_modules");
+    assert!(library_dir.join("godot-rust-cli.json").exists());
+    assert!(test_dir
+        .join("platformer")
+        .join("gdnative")
+        .join("platformer_modules.gdnlib")
+        .exists());
+    assert_eq!(
+        project.godot_project_path(),
+        dunce::canonicalize(test_dir.join("platformer"))?
+    );
+
+    // 2. Open the library and create a module in it.
+    let mut project = Project::open(&library_dir)?;
+    project.create_module("Player", ModuleOptions::default())?;
+    assert!(library_dir.join("src").join("player.rs").exists());
+    assert!(test_dir
+        .join("platformer")
+        .join("gdnative")
+        .join("player.gdns")
+        .exists());
+    assert_eq!(project.config().modules, vec!["Player"]);
+
+    // 3. Assert that the module was saved to the config.
+    let config = read_to_string(library_dir.join("godot-rust-cli.json"))?;
+    let config_json: Value = serde_json::from_str(&config)?;
+    assert_eq!(config_json["modules"], json!(["Player"]));
+
+    remove_dir_all(&test_dir)?;
+
+    Ok(())
+}
+
+/// Opens a directory that isn't a library and checks that an error is
+/// returned.
+#[test]
+fn project_open_missing_config() {
+    let test_dir = create_test_dir("project_open_missing_config");
+
+    let result = Project::open(&test_dir);
+    assert!(matches!(
+        result,
+        Err(godot_rust_cli::Error::ConfigMissing(_))
+    ));
+
+    remove_dir_all(&test_dir).expect("Unable to remove test dir");
+}
@@ -5,6 +5,7 @@ use std::path::Path;
 use std::process::Command;
 
 /// The structure of the gdnlib file.
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Gdnlib {
     pub general: GdnlibGeneral,
@@ -13,6 +14,7 @@ pub struct Gdnlib {
 }
 
 /// The structure of the general section of the gdnlib file.
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GdnlibGeneral {
     pub singleton: bool,
@@ -47,7 +49,8 @@ pub fn create_godot_project() {
 #[allow(dead_code)]
 pub fn create_godot_project_nested() {
     std::fs::create_dir_all("games/platformer").expect("Unable to create Godot project directory");
-    std::fs::File::create("games/platformer/project.godot").expect("Unable to create godot.project file");
+    std::fs::File::create("games/platformer/project.godot")
+        .expect("Unable to create godot.project file");
 }
 
 /// Since tests create folders and files we need to remove them before running
@@ -131,7 +134,7 @@ fn find_file(file_to_find: String) -> std::path::PathBuf {
         }
     }
 
-    return dir_to_check.to_owned();
+    dir_to_check.to_owned()
 }
 
 /// Runs before each test.
@@ -0,0 +1,130 @@
+use std::convert::TryInto;
+use std::error::Error;
+use std::fs::{create_dir_all, read, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+
+use godot_rust_cli::create_universal_binary;
+
+/// Returns a directory to write the binaries for a test to. The directory is
+/// created from scratch so that no files are left over from previous runs.
+///
+/// # Arguments
+///
+/// `name` - The name of the directory.
+fn create_test_dir(name: &str) -> PathBuf {
+    let test_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(name);
+    if test_dir.exists() {
+        remove_dir_all(&test_dir).expect("Unable to remove test dir");
+    }
+
+    create_dir_all(&test_dir).expect("Unable to create test dir");
+
+    test_dir
+}
+
+/// Returns the contents of a thin 64-bit little-endian Mach-O binary with a
+/// header for an architecture followed by some bytes.
+///
+/// # Arguments
+///
+/// `cpu_type` - The cpu type of the binary.
+/// `cpu_subtype` - The cpu subtype of the binary.
+/// `len` - The length of the binary.
+fn create_thin_binary(cpu_type: u32, cpu_subtype: u32, len: usize) -> Vec<u8> {
+    let mut thin_binary: Vec<u8> = vec![];
+    thin_binary.extend_from_slice(&0xfeed_facf_u32.to_le_bytes());
+    thin_binary.extend_from_slice(&cpu_type.to_le_bytes());
+    thin_binary.extend_from_slice(&cpu_subtype.to_le_bytes());
+    thin_binary.extend((thin_binary.len()..len).map(|index| (index % 251) as u8));
+
+    thin_binary
+}
+
+/// Reads a big-endian u32 from a universal binary.
+///
+/// # Arguments
+///
+/// `bytes` - The contents of the universal binary.
+/// `index` - The index to read the u32 at.
+fn read_be_u32(bytes: &[u8], index: usize) -> u32 {
+    u32::from_be_bytes(bytes[index..index + 4].try_into().unwrap())
+}
+
+/// Merges an x86_64 and an arm64 binary and checks that the universal binary
+/// has a fat header with an aligned slice for each one that's identical to
+/// the binary that it came from.
+#[test]
+fn universal_binary_merges_thin_binaries() -> Result<(), Box<dyn Error>> {
+    let test_dir = create_test_dir("universal_binary_merges_thin_binaries");
+
+    // 1. Write the thin binaries and merge them.
+    let x86_64_binary = create_thin_binary(0x0100_0007, 0x8000_0003, 5000);
+    let arm64_binary = create_thin_binary(0x0100_000c, 0, 3000);
+    let x86_64_path = test_dir.join("x86_64.dylib");
+    let arm64_path = test_dir.join("arm64.dylib");
+    write(&x86_64_path, &x86_64_binary)?;
+    write(&arm64_path, &arm64_binary)?;
+
+    let universal_path = test_dir.join("universal.dylib");
+    create_universal_binary(&[x86_64_path, arm64_path], &universal_path)?;
+
+    // 2. Assert that the header is for a universal binary with two
+    // architectures.
+    let universal_binary = read(&universal_path)?;
+    assert_eq!(read_be_u32(&universal_binary, 0), 0xcafe_babe);
+    assert_eq!(read_be_u32(&universal_binary, 4), 2);
+
+    // 3. Assert that each architecture is described by its entry and that
+    // its slice is aligned and identical to its binary.
+    let expected = [
+        (0x0100_0007, 0x8000_0003, 12, &x86_64_binary),
+        (0x0100_000c, 0, 14, &arm64_binary),
+    ];
+    for (index, (cpu_type, cpu_subtype, align, thin_binary)) in expected.iter().enumerate() {
+        let entry = 8 + index * 20;
+        assert_eq!(read_be_u32(&universal_binary, entry), *cpu_type);
+        assert_eq!(read_be_u32(&universal_binary, entry + 4), *cpu_subtype);
+
+        let offset = read_be_u32(&universal_binary, entry + 8) as usize;
+        let size = read_be_u32(&universal_binary, entry + 12) as usize;
+        assert_eq!(read_be_u32(&universal_binary, entry + 16), *align);
+        assert_eq!(offset % (1 << align), 0);
+        assert_eq!(size, thin_binary.len());
+        assert_eq!(
+            &universal_binary[offset..offset + size],
+            thin_binary.as_slice()
+        );
+    }
+
+    remove_dir_all(&test_dir)?;
+
+    Ok(())
+}
+
+/// Tries to merge binaries that aren't thin Mach-O binaries or that have
+/// the same architecture and checks that both fail without writing anything.
+#[test]
+fn universal_binary_rejects_invalid_binaries() -> Result<(), Box<dyn Error>> {
+    let test_dir = create_test_dir("universal_binary_rejects_invalid_binaries");
+
+    // 1. Assert that a file that isn't a Mach-O binary can't be merged.
+    let elf_path = test_dir.join("libplatformer_modules.so");
+    let arm64_path = test_dir.join("arm64.dylib");
+    write(&elf_path, b"\x7fELF\x02\x01\x01\x00")?;
+    write(&arm64_path, create_thin_binary(0x0100_000c, 0, 64))?;
+
+    let universal_path = test_dir.join("universal.dylib");
+    assert!(create_universal_binary(&[elf_path, arm64_path.clone()], &universal_path).is_err());
+
+    // 2. Assert that two binaries for the same architecture can't be merged.
+    assert!(create_universal_binary(&[arm64_path.clone(), arm64_path], &universal_path).is_err());
+
+    // 3. Assert that the universal binary wasn't written.
+    assert!(!universal_path.exists());
+
+    remove_dir_all(&test_dir)?;
+
+    Ok(())
+}